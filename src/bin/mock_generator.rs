@@ -0,0 +1,180 @@
+use scrb::{
+    components::noun::is_noun_slug,
+    plugins::generator::http::{JobCreated, JobRequest, JobState, JobStatus},
+};
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Read, Write},
+    net::{TcpListener, TcpStream},
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+// How long every job pretends to run.
+const JOB_DURATION: Duration = Duration::from_secs(3);
+
+struct Job {
+    noun: String,
+    started: Instant,
+}
+
+/// Serves canned meshes over the same job protocol as a real generation server.
+///
+/// `cargo run --bin mock_generator -- [address] [canned dir]`
+///
+/// A job for `<noun>` returns `<canned dir>/<noun>/mesh.glb` if it exists and
+/// `<canned dir>/mystery_block/mesh.glb` otherwise. Nouns starting with `fail`
+/// end in a failed job.
+fn main() {
+    let mut args = std::env::args().skip(1);
+    let address = args.next().unwrap_or_else(|| "127.0.0.1:8000".to_string());
+    let canned = PathBuf::from(args.next().unwrap_or_else(|| "assets/models".to_string()));
+
+    let listener = TcpListener::bind(&address).unwrap();
+    println!("Mock generator listening on http://{}", address);
+
+    let mut jobs: Vec<Job> = vec![];
+
+    for stream in listener.incoming() {
+        let Ok(mut stream) = stream else {
+            continue;
+        };
+
+        if let Err(err) = handle(&mut stream, &mut jobs, &canned) {
+            println!("Request failed: {}", err);
+        }
+    }
+}
+
+fn handle(stream: &mut TcpStream, jobs: &mut Vec<Job>, canned: &Path) -> std::io::Result<()> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line)?;
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().unwrap_or_default().to_string();
+    let path = parts.next().unwrap_or_default().to_string();
+
+    let mut headers = HashMap::new();
+    loop {
+        let mut line = String::new();
+        reader.read_line(&mut line)?;
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            headers.insert(name.trim().to_lowercase(), value.trim().to_string());
+        }
+    }
+
+    let length: usize = headers
+        .get("content-length")
+        .and_then(|length| length.parse().ok())
+        .unwrap_or(0);
+    let mut body = vec![0; length];
+    reader.read_exact(&mut body)?;
+
+    println!("{} {}", method, path);
+
+    let segments: Vec<&str> = path.trim_matches('/').split('/').collect();
+
+    match (method.as_str(), segments.as_slice()) {
+        ("POST", ["jobs"]) => {
+            let Ok(request) = serde_json::from_slice::<JobRequest>(&body) else {
+                return respond(stream, "400 Bad Request", "text/plain", b"invalid job");
+            };
+
+            // the noun is joined onto the canned directory
+            if !is_noun_slug(&request.noun) {
+                return respond(stream, "400 Bad Request", "text/plain", b"invalid noun");
+            }
+
+            jobs.push(Job {
+                noun: request.noun,
+                started: Instant::now(),
+            });
+
+            let created = JobCreated {
+                id: (jobs.len() - 1).to_string(),
+            };
+            respond_json(stream, "201 Created", &created)
+        }
+        ("GET", ["jobs", id]) => {
+            let Some(job) = find_job(jobs, id) else {
+                return respond(stream, "404 Not Found", "text/plain", b"no such job");
+            };
+
+            let elapsed = job.started.elapsed();
+            let status = if elapsed < JOB_DURATION {
+                JobStatus {
+                    state: JobState::Running,
+                    progress: Some(elapsed.as_secs_f32() / JOB_DURATION.as_secs_f32()),
+                    mesh_url: None,
+                    error: None,
+                }
+            } else if job.noun.starts_with("fail") {
+                JobStatus {
+                    state: JobState::Failed,
+                    progress: None,
+                    mesh_url: None,
+                    error: Some(format!("refusing to generate {}", job.noun)),
+                }
+            } else {
+                JobStatus {
+                    state: JobState::Done,
+                    progress: Some(1.0),
+                    mesh_url: Some(format!("/jobs/{}/mesh.glb", id)),
+                    error: None,
+                }
+            };
+
+            respond_json(stream, "200 OK", &status)
+        }
+        ("GET", ["jobs", id, "mesh.glb"]) => {
+            let Some(job) = find_job(jobs, id) else {
+                return respond(stream, "404 Not Found", "text/plain", b"no such job");
+            };
+
+            let own = canned.join(&job.noun).join("mesh.glb");
+            let mesh = if own.exists() {
+                own
+            } else {
+                canned.join("mystery_block").join("mesh.glb")
+            };
+
+            respond(stream, "200 OK", "model/gltf-binary", &std::fs::read(mesh)?)
+        }
+        _ => respond(stream, "404 Not Found", "text/plain", b"not found"),
+    }
+}
+
+fn find_job<'a>(jobs: &'a [Job], id: &str) -> Option<&'a Job> {
+    jobs.get(id.parse::<usize>().ok()?)
+}
+
+fn respond_json<T: serde::Serialize>(
+    stream: &mut TcpStream,
+    status: &str,
+    body: &T,
+) -> std::io::Result<()> {
+    let body = serde_json::to_vec(body)?;
+    respond(stream, status, "application/json", &body)
+}
+
+fn respond(
+    stream: &mut TcpStream,
+    status: &str,
+    content_type: &str,
+    body: &[u8],
+) -> std::io::Result<()> {
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    )?;
+    stream.write_all(body)?;
+    stream.flush()
+}
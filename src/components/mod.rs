@@ -1,2 +1,3 @@
 pub mod core;
 pub mod modifier;
+pub mod noun;
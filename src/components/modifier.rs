@@ -10,7 +10,7 @@ use bevy::prelude::*;
 use bevy::render::color::Color;
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Reflect)]
 pub enum Modifier {
     ColorModifier(Color),
     ScaleModifier(f32),
@@ -18,7 +18,7 @@ pub enum Modifier {
     MetallicModifier(f32),
     ReflectanceModifier(f32),
 }
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Reflect)]
 pub struct ModifierName {
     pub name: String,
     pub modifier: Vec<Modifier>,
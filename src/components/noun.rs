@@ -0,0 +1,235 @@
+use std::{collections::HashMap, fs::OpenOptions, path::Path};
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// Longest noun accepted as a folder name in `assets/models`.
+const MAX_SLUG_LEN: usize = 64;
+
+/// Turns typed input into a noun that is safe to use as a folder name and to send to
+/// a generator: lowercase ASCII letters and digits joined by single underscores.
+/// Spaces and dashes become underscores, anything else is dropped, e.g.
+/// `King-Cobra!` becomes `king_cobra` and `../../etc` becomes `etc`.
+pub fn noun_slug(input: &str) -> Option<String> {
+    let mut slug = String::new();
+
+    for c in input.chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if matches!(c, ' ' | '-' | '_') && !slug.is_empty() && !slug.ends_with('_') {
+            slug.push('_');
+        }
+    }
+
+    let slug = slug.trim_end_matches('_');
+    (!slug.is_empty() && slug.len() <= MAX_SLUG_LEN).then(|| slug.to_string())
+}
+
+/// Whether `noun` is already in the form [`noun_slug`] produces.
+pub fn is_noun_slug(noun: &str) -> bool {
+    noun_slug(noun).as_deref() == Some(noun)
+}
+
+/// Colliders computed from the loaded model once its meshes are available.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MeshCollider {
+    ConvexHull,
+    Aabb,
+    ConvexDecomposition,
+}
+
+/// Collider shapes a noun can declare in its `noun.ron`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub enum ColliderShape {
+    /// Half extents along x, y and z.
+    Cuboid(f32, f32, f32),
+    /// Radius.
+    Ball(f32),
+    /// Half height and radius, standing along y.
+    Capsule(f32, f32),
+    /// Half height and radius, standing along y.
+    Cylinder(f32, f32),
+    /// Computed from the model, see [`MeshCollider`].
+    Mesh(MeshCollider),
+}
+
+impl ColliderShape {
+    pub fn collider(&self) -> Collider {
+        match *self {
+            ColliderShape::Cuboid(x, y, z) => Collider::cuboid(x, y, z),
+            ColliderShape::Ball(radius) => Collider::ball(radius),
+            ColliderShape::Capsule(half_height, radius) => Collider::capsule_y(half_height, radius),
+            ColliderShape::Cylinder(half_height, radius) => Collider::cylinder(half_height, radius),
+            // unit placeholder, pivoted at the bottom like normalized models
+            ColliderShape::Mesh(_) => Collider::compound(vec![(
+                Vec3::new(0.0, 0.5, 0.0),
+                Quat::IDENTITY,
+                Collider::cuboid(0.5, 0.5, 0.5),
+            )]),
+        }
+    }
+
+    pub fn mesh_collider(&self) -> Option<MeshCollider> {
+        match *self {
+            ColliderShape::Mesh(kind) => Some(kind),
+            _ => None,
+        }
+    }
+}
+
+impl Default for ColliderShape {
+    fn default() -> Self {
+        ColliderShape::Cuboid(0.5, 0.5, 0.5)
+    }
+}
+
+/// Physical defaults for a noun, read from `assets/models/<noun>/noun.ron`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+#[serde(default)]
+pub struct NounEntry {
+    pub collider: ColliderShape,
+    pub scale: f32,
+    pub density: f32,
+    /// Euler angles (x, y, z) in radians that stand the model upright before it is normalized.
+    pub rotation: (f32, f32, f32),
+    pub tags: Vec<String>,
+}
+
+impl NounEntry {
+    pub fn primitive(collider: ColliderShape) -> Self {
+        NounEntry {
+            collider,
+            rotation: (0.0, 0.0, 0.0),
+            tags: vec!["primitive".to_string()],
+            ..default()
+        }
+    }
+
+    pub fn orientation(&self) -> Quat {
+        let (x, y, z) = self.rotation;
+        Quat::from_euler(EulerRot::XYZ, x, y, z)
+    }
+}
+
+impl Default for NounEntry {
+    fn default() -> Self {
+        NounEntry {
+            collider: ColliderShape::Mesh(MeshCollider::ConvexHull),
+            scale: 1.0,
+            density: 1.0,
+            // generated meshes come out lying on their side
+            rotation: (
+                -std::f32::consts::FRAC_PI_2,
+                0.0,
+                -std::f32::consts::FRAC_PI_2,
+            ),
+            tags: vec![],
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct NounRegistry {
+    pub entries: HashMap<String, NounEntry>,
+}
+
+impl NounRegistry {
+    pub fn new() -> Self {
+        let mut entries = HashMap::new();
+        entries.insert(
+            "cube".to_string(),
+            NounEntry::primitive(ColliderShape::Cuboid(0.5, 0.5, 0.5)),
+        );
+        entries.insert(
+            "ball".to_string(),
+            NounEntry::primitive(ColliderShape::Ball(0.5)),
+        );
+
+        Self { entries }
+    }
+
+    pub fn get(&mut self, noun: &str) -> NounEntry {
+        if let Some(entry) = self.entries.get(noun) {
+            return entry.clone();
+        }
+
+        // misses are not cached: the model and its data file may be generated later
+        match Self::import(noun) {
+            Some(entry) => {
+                self.entries.insert(noun.to_string(), entry.clone());
+                entry
+            }
+            None => NounEntry::default(),
+        }
+    }
+
+    pub fn import(noun: &str) -> Option<NounEntry> {
+        let file_path = Path::new("assets/models").join(noun).join("noun.ron");
+
+        let file = OpenOptions::new().read(true).open(file_path).ok()?;
+
+        match ron::de::from_reader(file) {
+            Ok(entry) => Some(entry),
+            Err(err) => {
+                println!("Failed to read noun data for {}: {}", noun, err);
+                None
+            }
+        }
+    }
+}
+
+impl Default for NounRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn slug_examples() {
+        assert_eq!(noun_slug("King-Cobra!").as_deref(), Some("king_cobra"));
+        assert_eq!(noun_slug("../../etc").as_deref(), Some("etc"));
+        assert_eq!(noun_slug("lion king").as_deref(), Some("lion_king"));
+    }
+
+    #[test]
+    fn slug_collapses_and_trims_separators() {
+        assert_eq!(noun_slug("  big -- cake_ ").as_deref(), Some("big_cake"));
+        assert_eq!(noun_slug("_cake").as_deref(), Some("cake"));
+    }
+
+    #[test]
+    fn slug_drops_path_and_non_ascii_characters() {
+        assert_eq!(noun_slug("/etc/passwd").as_deref(), Some("etcpasswd"));
+        assert_eq!(noun_slug("C:\\models").as_deref(), Some("cmodels"));
+        assert_eq!(noun_slug("café").as_deref(), Some("caf"));
+    }
+
+    #[test]
+    fn slug_rejects_empty_and_overlong_nouns() {
+        assert_eq!(noun_slug(""), None);
+        assert_eq!(noun_slug("../.."), None);
+        assert_eq!(noun_slug("!? -"), None);
+        assert_eq!(
+            noun_slug(&"a".repeat(MAX_SLUG_LEN)).map(|s| s.len()),
+            Some(MAX_SLUG_LEN)
+        );
+        assert_eq!(noun_slug(&"a".repeat(MAX_SLUG_LEN + 1)), None);
+    }
+
+    #[test]
+    fn is_noun_slug_only_accepts_slugs() {
+        assert!(is_noun_slug("king_cobra"));
+        assert!(is_noun_slug("cube2"));
+        assert!(!is_noun_slug("King_Cobra"));
+        assert!(!is_noun_slug("king__cobra"));
+        assert!(!is_noun_slug("king_cobra_"));
+        assert!(!is_noun_slug(".."));
+        assert!(!is_noun_slug("a/b"));
+        assert!(!is_noun_slug(""));
+    }
+}
@@ -19,6 +19,30 @@ fn main() {
         .insert_resource(DebugPickingMode::Normal)
         .add_plugins(TextInputPlugin)
         .add_plugins(HookPlugin)
-        .add_plugins((CustomAssetPlugin, WorldPlugin, PlayerPlugin, MainUiPlugin))
+        .add_plugins((
+            CustomAssetPlugin,
+            CommandPlugin,
+            GeneratorPlugin,
+            ModelLibraryPlugin,
+            WorldPlugin,
+            PlayerPlugin,
+            PlacementPlugin,
+            MeshColliderPlugin,
+            MeshNormalizationPlugin,
+            MainUiPlugin,
+        ))
+        .add_plugins((
+            GenerationProgressPlugin,
+            GenerationFallbackPlugin,
+            NotificationPlugin,
+            LibraryBrowserPlugin,
+            HistoryPlugin,
+            SavePlugin,
+            AutosavePlugin,
+            ScriptPlugin,
+            InputHistoryPlugin,
+            PhysicsControlPlugin,
+            StructurePlugin,
+        ))
         .run();
 }
@@ -1,7 +1,7 @@
 use bevy::{gltf::Gltf, prelude::*};
 use bevy_asset_loader::prelude::*;
 
-use crate::components::modifier::Dictionary;
+use crate::components::{modifier::Dictionary, noun::NounRegistry};
 
 #[derive(Clone, Eq, PartialEq, Debug, Hash, Default, States)]
 pub enum AssetLoadingState {
@@ -24,6 +24,7 @@ impl Plugin for CustomAssetPlugin {
                     .continue_to_state(AssetLoadingState::Next)
                     .load_collection::<PlayerAssets>(),
             )
+            .init_resource::<NounRegistry>()
             .add_systems(Startup, load_dictionary);
     }
 }
@@ -0,0 +1,176 @@
+use std::{
+    path::{Path, PathBuf},
+    time::SystemTime,
+};
+
+use bevy::{app::AppExit, prelude::*};
+
+use super::{
+    command::{CommandFeedback, CommandInvocation, CommandSet, CommandSpec, RegisterCommand},
+    history::{History, ObjectSpawned, RestoreObject},
+    notification::Notification,
+    player::Player,
+    save::{PlayerQuery, SandboxSave, SaveLoaded, SavedObjectQuery, AUTOSAVE_PREFIX, SAVES_DIR},
+    ui::SpawnedObject,
+};
+
+const AUTOSAVE_INTERVAL: f32 = 60.0;
+/// Autosaves are written to this many files in turn.
+const AUTOSAVE_SLOTS: usize = 3;
+/// Exists while the game runs, written when it starts; left behind by a crash.
+const SESSION_LOCK: &str = "session.lock";
+
+pub struct AutosavePlugin;
+
+impl Plugin for AutosavePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(Autosave::start())
+            .register_command(CommandSpec::new(
+                "recover",
+                "Restores the last autosave of a session that did not exit cleanly",
+            ))
+            .add_systems(Startup, offer_recovery)
+            .add_systems(
+                Update,
+                (autosave, run_recover_command.in_set(CommandSet::Handle)),
+            )
+            .add_systems(Last, end_session.run_if(on_event::<AppExit>()));
+    }
+}
+
+#[derive(Resource)]
+pub struct Autosave {
+    timer: Timer,
+    next_slot: usize,
+    /// The newest autosave of a crashed session, until it is recovered or the
+    /// player starts over. Autosaving waits so that it is not overwritten.
+    recovery: Option<PathBuf>,
+}
+
+impl Autosave {
+    fn start() -> Self {
+        // when the crashed session started, so older autosaves are not taken for its own
+        let crashed_at = std::fs::metadata(session_lock())
+            .and_then(|metadata| metadata.modified())
+            .ok();
+        let latest = latest_autosave();
+
+        if let Err(err) = std::fs::create_dir_all(SAVES_DIR)
+            .and_then(|_| std::fs::write(session_lock(), std::process::id().to_string()))
+        {
+            println!("Failed to create {}: {}", session_lock().display(), err);
+        }
+
+        Autosave {
+            timer: Timer::from_seconds(AUTOSAVE_INTERVAL, TimerMode::Repeating),
+            // the newest autosave is the last to be overwritten
+            next_slot: latest
+                .as_ref()
+                .map_or(0, |(slot, ..)| (slot + 1) % AUTOSAVE_SLOTS),
+            recovery: latest
+                .filter(|(_, _, modified)| crashed_at.is_some_and(|started| *modified >= started))
+                .map(|(_, path, _)| path),
+        }
+    }
+}
+
+fn session_lock() -> PathBuf {
+    Path::new(SAVES_DIR).join(SESSION_LOCK)
+}
+
+fn autosave_path(slot: usize) -> PathBuf {
+    SandboxSave::path(&format!("{AUTOSAVE_PREFIX}_{slot}"))
+}
+
+/// The most recently written autosave slot, its path and when it was written.
+fn latest_autosave() -> Option<(usize, PathBuf, SystemTime)> {
+    (0..AUTOSAVE_SLOTS)
+        .filter_map(|slot| {
+            let path = autosave_path(slot);
+            let modified = std::fs::metadata(&path).and_then(|m| m.modified()).ok()?;
+            Some((slot, path, modified))
+        })
+        .max_by_key(|(.., modified)| *modified)
+}
+
+fn offer_recovery(autosave: Res<Autosave>, mut notifications: EventWriter<Notification>) {
+    if autosave.recovery.is_some() {
+        notifications.send(Notification::new(
+            "The last session ended unexpectedly, type /recover to restore it",
+        ));
+    }
+}
+
+fn autosave(
+    time: Res<Time>,
+    mut autosave: ResMut<Autosave>,
+    mut spawned: EventReader<ObjectSpawned>,
+    mut loaded: EventReader<SaveLoaded>,
+    saved_query: SavedObjectQuery,
+    player_query: Query<&Transform, (With<Player>, Without<SpawnedObject>)>,
+) {
+    // spawning something new or loading a save means the crashed session is not wanted
+    if spawned.read().count() > 0 || loaded.read().count() > 0 {
+        autosave.recovery = None;
+    }
+
+    if !autosave.timer.tick(time.delta()).just_finished() || autosave.recovery.is_some() {
+        return;
+    }
+
+    let path = autosave_path(autosave.next_slot);
+    let save = SandboxSave::capture(&saved_query, player_query.get_single().ok());
+
+    match save.export(&path) {
+        Ok(()) => autosave.next_slot = (autosave.next_slot + 1) % AUTOSAVE_SLOTS,
+        Err(err) => println!("Failed to autosave to {}: {}", path.display(), err),
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_recover_command(
+    mut commands: Commands,
+    mut autosave: ResMut<Autosave>,
+    mut history: ResMut<History>,
+    mut invocations: EventReader<CommandInvocation>,
+    mut restores: EventWriter<RestoreObject>,
+    mut feedback: CommandFeedback,
+    object_query: Query<Entity, With<SpawnedObject>>,
+    mut player_query: PlayerQuery,
+) {
+    for invocation in invocations.read() {
+        if invocation.name != "recover" {
+            continue;
+        }
+
+        let Some(path) = autosave.recovery.clone() else {
+            feedback.send("Nothing to recover");
+            continue;
+        };
+        // kept on failure, so that it is not overwritten by the next autosave
+        let save = match SandboxSave::import(&path) {
+            Ok(save) => save,
+            Err(err) => {
+                feedback.send(format!("Failed to recover {}: {}", path.display(), err));
+                continue;
+            }
+        };
+
+        autosave.recovery = None;
+        save.restore(
+            &mut commands,
+            &mut history,
+            &mut restores,
+            &object_query,
+            &mut player_query,
+        );
+        feedback.send(format!("Recovered {} objects", save.objects.len()));
+    }
+}
+
+/// Removes the session lock, so the next start knows this one exited cleanly.
+fn end_session() {
+    if let Err(err) = std::fs::remove_file(session_lock()) {
+        println!("Failed to remove {}: {}", session_lock().display(), err);
+    }
+}
@@ -0,0 +1,366 @@
+use std::path::Path;
+
+use bevy::prelude::*;
+
+use crate::components::noun::NounRegistry;
+
+use super::{
+    generator::{queue::GenerationQueue, Generator},
+    library::ModelLibrary,
+    notification::Notification,
+    ui::{SpawnPhrase, TypingState, PLACEHOLDER_NOUN},
+};
+
+const PANEL_WIDTH: f32 = 320.0;
+const THUMBNAIL_SIZE: f32 = 64.0;
+const FONT_SIZE: f32 = 18.0;
+const TEXT_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
+const PANEL_COLOR: Color = Color::rgba(0.15, 0.15, 0.15, 0.9);
+const ROW_COLOR: Color = Color::rgb(0.2, 0.2, 0.2);
+const BUTTON_COLOR: Color = Color::rgb(0.3, 0.3, 0.3);
+const BUTTON_HOVERED_COLOR: Color = Color::rgb(0.75, 0.52, 0.99);
+const MISSING_THUMBNAIL_COLOR: Color = Color::rgb(0.1, 0.1, 0.1);
+/// Seconds a Regen or Delete button waits for the second press that confirms it.
+const CONFIRM_TIME: f32 = 3.0;
+
+pub struct LibraryBrowserPlugin;
+
+impl Plugin for LibraryBrowserPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                toggle_library_browser.run_if(in_state(TypingState::IsMoving)),
+                refresh_library_browser,
+                on_library_button,
+                expire_library_confirmations,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// Side panel listing every model in the [`ModelLibrary`], toggled with Tab.
+#[derive(Component)]
+struct LibraryBrowser;
+
+/// Holds one row per model and is rebuilt whenever the library changes.
+#[derive(Component)]
+struct LibraryList;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LibraryAction {
+    Spawn,
+    Regenerate,
+    Delete,
+}
+
+impl LibraryAction {
+    fn label(&self) -> &'static str {
+        match self {
+            LibraryAction::Spawn => "Spawn",
+            LibraryAction::Regenerate => "Regen",
+            LibraryAction::Delete => "Delete",
+        }
+    }
+
+    /// Whether the button has to be pressed twice, since the model files are replaced.
+    fn needs_confirmation(&self) -> bool {
+        matches!(self, LibraryAction::Regenerate | LibraryAction::Delete)
+    }
+}
+
+#[derive(Component)]
+struct LibraryButton {
+    noun: String,
+    action: LibraryAction,
+    /// Running after a first press, until which a second one goes through.
+    confirming: Option<Timer>,
+}
+
+fn toggle_library_browser(
+    mut commands: Commands,
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    asset_server: Res<AssetServer>,
+    library: Res<ModelLibrary>,
+    generator: Res<Generator>,
+    browser_query: Query<Entity, With<LibraryBrowser>>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Tab) {
+        return;
+    }
+
+    if let Ok(browser) = browser_query.get_single() {
+        commands.entity(browser).despawn_recursive();
+        return;
+    }
+
+    commands
+        .spawn((
+            LibraryBrowser,
+            NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    right: Val::Px(0.0),
+                    width: Val::Px(PANEL_WIDTH),
+                    height: Val::Percent(100.0),
+                    flex_direction: FlexDirection::Column,
+                    padding: UiRect::all(Val::Px(8.0)),
+                    row_gap: Val::Px(8.0),
+                    overflow: Overflow::clip_y(),
+                    ..default()
+                },
+                background_color: PANEL_COLOR.into(),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section(
+                "Library",
+                TextStyle {
+                    font_size: FONT_SIZE * 1.5,
+                    color: TEXT_COLOR,
+                    ..default()
+                },
+            ));
+
+            parent
+                .spawn((
+                    LibraryList,
+                    NodeBundle {
+                        style: Style {
+                            flex_direction: FlexDirection::Column,
+                            row_gap: Val::Px(4.0),
+                            ..default()
+                        },
+                        ..default()
+                    },
+                ))
+                .with_children(|list| {
+                    spawn_library_rows(list, &asset_server, &library, generator.is_offline())
+                });
+        });
+}
+
+fn refresh_library_browser(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    library: Res<ModelLibrary>,
+    generator: Res<Generator>,
+    list_query: Query<Entity, With<LibraryList>>,
+) {
+    if !library.is_changed() {
+        return;
+    }
+
+    for list in list_query.iter() {
+        commands
+            .entity(list)
+            .despawn_descendants()
+            .with_children(|list| {
+                spawn_library_rows(list, &asset_server, &library, generator.is_offline())
+            });
+    }
+}
+
+fn spawn_library_rows(
+    list: &mut ChildBuilder,
+    asset_server: &AssetServer,
+    library: &ModelLibrary,
+    offline: bool,
+) {
+    let text_style = TextStyle {
+        font_size: FONT_SIZE,
+        color: TEXT_COLOR,
+        ..default()
+    };
+
+    for (noun, metadata) in library.list() {
+        list.spawn(NodeBundle {
+            style: Style {
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(8.0),
+                padding: UiRect::all(Val::Px(4.0)),
+                ..default()
+            },
+            background_color: ROW_COLOR.into(),
+            ..default()
+        })
+        .with_children(|row| {
+            let thumbnail_style = Style {
+                width: Val::Px(THUMBNAIL_SIZE),
+                height: Val::Px(THUMBNAIL_SIZE),
+                flex_shrink: 0.0,
+                ..default()
+            };
+
+            // hand-made and procedural models have no input image
+            if Path::new("assets/models")
+                .join(noun)
+                .join("processed_input.png")
+                .exists()
+            {
+                row.spawn(ImageBundle {
+                    style: thumbnail_style,
+                    image: UiImage::new(
+                        asset_server.load(format!("models/{noun}/processed_input.png")),
+                    ),
+                    ..default()
+                });
+            } else {
+                row.spawn(NodeBundle {
+                    style: thumbnail_style,
+                    background_color: MISSING_THUMBNAIL_COLOR.into(),
+                    ..default()
+                });
+            }
+
+            row.spawn(NodeBundle {
+                style: Style {
+                    flex_direction: FlexDirection::Column,
+                    row_gap: Val::Px(4.0),
+                    ..default()
+                },
+                ..default()
+            })
+            .with_children(|details| {
+                let mut label = noun.clone();
+                if !metadata.tags.is_empty() {
+                    label.push_str(&format!(" [{}]", metadata.tags.join(", ")));
+                }
+                details.spawn(TextBundle::from_section(label, text_style.clone()));
+
+                details
+                    .spawn(NodeBundle {
+                        style: Style {
+                            column_gap: Val::Px(4.0),
+                            ..default()
+                        },
+                        ..default()
+                    })
+                    .with_children(|buttons| {
+                        // the placeholder cannot be replaced, nor anything while offline
+                        let actions = match (noun.as_str(), offline) {
+                            (PLACEHOLDER_NOUN, _) => vec![LibraryAction::Spawn],
+                            (_, true) => vec![LibraryAction::Spawn, LibraryAction::Delete],
+                            (_, false) => vec![
+                                LibraryAction::Spawn,
+                                LibraryAction::Regenerate,
+                                LibraryAction::Delete,
+                            ],
+                        };
+
+                        for action in actions {
+                            buttons
+                                .spawn((
+                                    LibraryButton {
+                                        noun: noun.clone(),
+                                        action,
+                                        confirming: None,
+                                    },
+                                    ButtonBundle {
+                                        style: Style {
+                                            padding: UiRect::axes(Val::Px(6.0), Val::Px(2.0)),
+                                            ..default()
+                                        },
+                                        background_color: BUTTON_COLOR.into(),
+                                        ..default()
+                                    },
+                                ))
+                                .with_children(|button| {
+                                    button.spawn(TextBundle::from_section(
+                                        action.label(),
+                                        text_style.clone(),
+                                    ));
+                                });
+                        }
+                    });
+            });
+        });
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn on_library_button(
+    mut library: ResMut<ModelLibrary>,
+    mut queue: ResMut<GenerationQueue>,
+    generator: Res<Generator>,
+    mut noun_registry: ResMut<NounRegistry>,
+    mut phrases: EventWriter<SpawnPhrase>,
+    mut notifications: EventWriter<Notification>,
+    mut button_query: Query<
+        (
+            &Interaction,
+            &mut LibraryButton,
+            &mut BackgroundColor,
+            &Children,
+        ),
+        Changed<Interaction>,
+    >,
+    mut text_query: Query<&mut Text>,
+) {
+    for (interaction, mut button, mut background, children) in button_query.iter_mut() {
+        match interaction {
+            Interaction::Pressed => {
+                if button.action.needs_confirmation() && button.confirming.is_none() {
+                    button.confirming = Some(Timer::from_seconds(CONFIRM_TIME, TimerMode::Once));
+                    set_button_label(children, &mut text_query, "Sure?");
+                    continue;
+                }
+                button.confirming = None;
+
+                let noun = &button.noun;
+                let message = match button.action {
+                    LibraryAction::Spawn => {
+                        phrases.send(SpawnPhrase(noun.clone()));
+                        continue;
+                    }
+                    LibraryAction::Regenerate => {
+                        match library.regenerate(noun, &mut queue, &generator) {
+                            Ok(()) => format!("Regenerating {}", noun),
+                            Err(err) => format!("Failed to regenerate {}: {}", noun, err),
+                        }
+                    }
+                    LibraryAction::Delete => match library.delete(noun) {
+                        Ok(()) => {
+                            noun_registry.entries.remove(noun);
+                            format!("Deleted {}", noun)
+                        }
+                        Err(err) => format!("Failed to delete {}: {}", noun, err),
+                    },
+                };
+
+                notifications.send(Notification(message));
+            }
+            Interaction::Hovered => *background = BUTTON_HOVERED_COLOR.into(),
+            Interaction::None => *background = BUTTON_COLOR.into(),
+        }
+    }
+}
+
+/// Takes back the first press of a Regen or Delete button that was not confirmed in time.
+fn expire_library_confirmations(
+    time: Res<Time>,
+    mut button_query: Query<(&mut LibraryButton, &Children)>,
+    mut text_query: Query<&mut Text>,
+) {
+    for (mut button, children) in button_query.iter_mut() {
+        // ticking alone does not mark the button as changed
+        let Some(timer) = button.bypass_change_detection().confirming.as_mut() else {
+            continue;
+        };
+
+        if timer.tick(time.delta()).finished() {
+            button.confirming = None;
+            set_button_label(children, &mut text_query, button.action.label());
+        }
+    }
+}
+
+fn set_button_label(children: &Children, text_query: &mut Query<&mut Text>, label: &str) {
+    for &child in children.iter() {
+        if let Ok(mut text) = text_query.get_mut(child) {
+            text.sections[0].value = label.to_string();
+        }
+    }
+}
@@ -0,0 +1,291 @@
+use std::{
+    collections::HashMap,
+    fs::OpenOptions,
+    io,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    prelude::*,
+    render::mesh::{Indices, VertexAttributeValues},
+    tasks::{block_on, poll_once, AsyncComputeTaskPool, Task},
+};
+use bevy_rapier3d::{
+    parry::{
+        math::Point,
+        transformation::{
+            try_convex_hull,
+            vhacd::{VHACDParameters, VHACD},
+        },
+    },
+    prelude::*,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::components::noun::MeshCollider;
+
+use super::{
+    library::ModelReplaced,
+    normalize::{Normalization, PendingNormalization},
+};
+
+pub struct MeshColliderPlugin;
+
+impl Plugin for MeshColliderPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            attach_collider_to_scene
+                .run_if(any_with_component::<PendingCollider>.or_else(on_event::<ModelReplaced>())),
+        );
+    }
+}
+
+/// Marks a scene whose collider should be computed from its meshes once they are loaded.
+#[derive(Component)]
+pub struct PendingCollider {
+    pub noun: String,
+    pub kind: MeshCollider,
+}
+
+/// Convex parts of a model's collider, cached in `assets/models/<noun>/collider.ron`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct CachedCollider {
+    pub kind: MeshCollider,
+    /// The hulls are measured in the normalized frame, so they go stale with it.
+    #[serde(default)]
+    pub normalization: Option<Normalization>,
+    pub hulls: Vec<Vec<(f32, f32, f32)>>,
+}
+
+impl CachedCollider {
+    fn path(noun: &str) -> PathBuf {
+        Path::new("assets/models").join(noun).join("collider.ron")
+    }
+
+    pub fn import(
+        noun: &str,
+        kind: MeshCollider,
+        normalization: Option<Normalization>,
+    ) -> Option<Self> {
+        let file = OpenOptions::new().read(true).open(Self::path(noun)).ok()?;
+        let cached: CachedCollider = ron::de::from_reader(file).ok()?;
+
+        // the noun may have switched to another kind, or the model been measured
+        // again, since the cache was written
+        (cached.kind == kind && cached.normalization == normalization).then_some(cached)
+    }
+
+    pub fn export(&self, noun: &str) {
+        let result = ron::ser::to_string_pretty(self, Default::default())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+            .and_then(|content| std::fs::write(Self::path(noun), content));
+
+        if let Err(err) = result {
+            println!("Failed to cache collider for {}: {}", noun, err);
+        }
+    }
+
+    pub fn compute(
+        kind: MeshCollider,
+        normalization: Option<Normalization>,
+        vertices: &[Vec3],
+        indices: &[[u32; 3]],
+    ) -> Self {
+        let points: Vec<Point<f32>> = vertices.iter().map(|v| Point::new(v.x, v.y, v.z)).collect();
+
+        let hulls: Vec<Vec<Point<f32>>> = match kind {
+            MeshCollider::ConvexHull => try_convex_hull(&points)
+                .map(|(hull, _)| vec![hull])
+                .unwrap_or_default(),
+            MeshCollider::Aabb => {
+                let min = vertices.iter().copied().fold(Vec3::MAX, Vec3::min);
+                let max = vertices.iter().copied().fold(Vec3::MIN, Vec3::max);
+
+                let corners = (0..8)
+                    .map(|i| {
+                        Point::new(
+                            if i & 1 == 0 { min.x } else { max.x },
+                            if i & 2 == 0 { min.y } else { max.y },
+                            if i & 4 == 0 { min.z } else { max.z },
+                        )
+                    })
+                    .collect();
+
+                vec![corners]
+            }
+            MeshCollider::ConvexDecomposition => {
+                VHACD::decompose(&VHACDParameters::default(), &points, indices, true)
+                    .compute_exact_convex_hulls(&points, indices)
+                    .into_iter()
+                    .map(|(hull, _)| hull)
+                    .collect()
+            }
+        };
+
+        CachedCollider {
+            kind,
+            normalization,
+            hulls: hulls
+                .into_iter()
+                .map(|hull| hull.iter().map(|p| (p.x, p.y, p.z)).collect())
+                .collect(),
+        }
+    }
+
+    pub fn collider(&self) -> Option<Collider> {
+        let mut parts: Vec<Collider> = self
+            .hulls
+            .iter()
+            .filter_map(|hull| {
+                let points: Vec<Vec3> = hull.iter().map(|&(x, y, z)| Vec3::new(x, y, z)).collect();
+                Collider::convex_hull(&points)
+            })
+            .collect();
+
+        match parts.len() {
+            0 => None,
+            1 => parts.pop(),
+            _ => Some(Collider::compound(
+                parts
+                    .into_iter()
+                    .map(|part| (Vec3::ZERO, Quat::IDENTITY, part))
+                    .collect(),
+            )),
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+pub fn attach_collider_to_scene(
+    mut commands: Commands,
+    mut computed: Local<HashMap<(String, MeshCollider), Option<Collider>>>,
+    mut computing: Local<HashMap<(String, MeshCollider), Task<CachedCollider>>>,
+    mut replaced: EventReader<ModelReplaced>,
+    meshes: Res<Assets<Mesh>>,
+    pending_query: Query<(Entity, &PendingCollider), Without<PendingNormalization>>,
+    children_query: Query<&Children>,
+    mesh_query: Query<&Handle<Mesh>>,
+    transform_query: Query<(&Transform, Option<&Parent>)>,
+) {
+    for ModelReplaced { noun } in replaced.read() {
+        computed.retain(|(computed_noun, _), _| computed_noun != noun);
+        computing.retain(|(computed_noun, _), _| computed_noun != noun);
+    }
+
+    // decompositions can take a while, so they run on the compute pool
+    computing.retain(|key, task| {
+        let Some(cached) = block_on(poll_once(task)) else {
+            return true;
+        };
+
+        cached.export(&key.0);
+        computed.insert(key.clone(), cached.collider());
+        false
+    });
+
+    for (entity, pending) in pending_query.iter() {
+        let key = (pending.noun.clone(), pending.kind);
+
+        if !computed.contains_key(&key) {
+            if computing.contains_key(&key) {
+                continue;
+            }
+
+            let normalization = Normalization::import(&pending.noun);
+            match CachedCollider::import(&pending.noun, pending.kind, normalization) {
+                Some(cached) => {
+                    computed.insert(key.clone(), cached.collider());
+                }
+                None => {
+                    // wait until the scene is spawned and all of its meshes are loaded
+                    let Some((vertices, indices)) = collect_scene_geometry(
+                        entity,
+                        &meshes,
+                        &children_query,
+                        &mesh_query,
+                        &transform_query,
+                    ) else {
+                        continue;
+                    };
+
+                    let kind = pending.kind;
+                    let task = AsyncComputeTaskPool::get().spawn(async move {
+                        CachedCollider::compute(kind, normalization, &vertices, &indices)
+                    });
+                    computing.insert(key, task);
+                    continue;
+                }
+            }
+        }
+
+        let mut entity_commands = commands.entity(entity);
+        entity_commands.remove::<PendingCollider>();
+
+        match &computed[&key] {
+            Some(collider) => {
+                entity_commands.insert(collider.clone());
+            }
+            None => println!("Could not compute a collider for {}", pending.noun),
+        }
+    }
+}
+
+/// Gathers the triangles of every mesh under `root`, expressed in the root's local space.
+pub fn collect_scene_geometry(
+    root: Entity,
+    meshes: &Assets<Mesh>,
+    children_query: &Query<&Children>,
+    mesh_query: &Query<&Handle<Mesh>>,
+    transform_query: &Query<(&Transform, Option<&Parent>)>,
+) -> Option<(Vec<Vec3>, Vec<[u32; 3]>)> {
+    let mut vertices = vec![];
+    let mut indices = vec![];
+
+    for descendant in children_query.iter_descendants(root) {
+        let Ok(handle) = mesh_query.get(descendant) else {
+            continue;
+        };
+
+        let mesh = meshes.get(handle)?;
+
+        let Some(VertexAttributeValues::Float32x3(positions)) =
+            mesh.attribute(Mesh::ATTRIBUTE_POSITION)
+        else {
+            continue;
+        };
+
+        // compose the local transforms up to the root, so this works before propagation
+        let mut matrix = Mat4::IDENTITY;
+        let mut current = descendant;
+        while current != root {
+            let (transform, parent) = transform_query.get(current).ok()?;
+            matrix = transform.compute_matrix() * matrix;
+            current = parent?.get();
+        }
+
+        let offset = vertices.len() as u32;
+        vertices.extend(
+            positions
+                .iter()
+                .map(|&p| matrix.transform_point3(Vec3::from(p))),
+        );
+
+        let triangle_indices: Vec<u32> = match mesh.indices() {
+            Some(Indices::U16(i)) => i.iter().map(|&i| i as u32).collect(),
+            Some(Indices::U32(i)) => i.clone(),
+            None => (0..positions.len() as u32).collect(),
+        };
+        indices.extend(
+            triangle_indices
+                .chunks_exact(3)
+                .map(|t| [t[0] + offset, t[1] + offset, t[2] + offset]),
+        );
+    }
+
+    if vertices.is_empty() {
+        None
+    } else {
+        Some((vertices, indices))
+    }
+}
@@ -0,0 +1,440 @@
+use std::collections::BTreeMap;
+
+use bevy::{ecs::system::SystemParam, prelude::*};
+use bevy_simple_text_input::TextInputValue;
+
+use crate::components::noun::noun_slug;
+
+use super::{notification::Notification, ui::TypingState};
+
+const HINT_FONT_SIZE: f32 = 18.0;
+const HINT_COLOR: Color = Color::rgb(0.7, 0.7, 0.7);
+
+pub struct CommandPlugin;
+
+impl Plugin for CommandPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<CommandRegistry>()
+            .add_event::<CommandLine>()
+            .add_event::<CommandInvocation>()
+            .configure_sets(
+                Update,
+                (CommandSet::Submit, CommandSet::Parse, CommandSet::Handle).chain(),
+            )
+            .register_command(
+                CommandSpec::new("help", "Lists commands, or explains one")
+                    .optional_arg("command", ArgKind::Word),
+            )
+            .add_systems(OnEnter(TypingState::IsTyping), spawn_command_hint)
+            .add_systems(OnExit(TypingState::IsTyping), despawn_command_hint)
+            .add_systems(
+                Update,
+                (
+                    parse_command_lines.in_set(CommandSet::Parse),
+                    run_help_command.in_set(CommandSet::Handle),
+                    (autocomplete_command, update_command_hint)
+                        .chain()
+                        .run_if(in_state(TypingState::IsTyping)),
+                ),
+            );
+    }
+}
+
+/// Steps of handling a typed line within a single frame, so that a line sees the
+/// effects of the commands before it. Phrases are spawned after [`CommandSet::Handle`].
+#[derive(SystemSet, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CommandSet {
+    /// Lines are sent as [`CommandLine`]s, or phrases to be spawned.
+    Submit,
+    /// [`CommandLine`]s are parsed into [`CommandInvocation`]s.
+    Parse,
+    /// Plugins handle their [`CommandInvocation`]s.
+    Handle,
+}
+
+/// What a command argument has to look like.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgKind {
+    Word,
+    /// A noun, turned into its slug.
+    Noun,
+    Number,
+    /// One of the listed words.
+    Choice(&'static [&'static str]),
+    /// Everything left on the line, spaces included.
+    Rest,
+}
+
+#[derive(Debug, Clone)]
+pub struct ArgSpec {
+    pub name: &'static str,
+    pub kind: ArgKind,
+    pub optional: bool,
+}
+
+impl ArgSpec {
+    fn usage(&self) -> String {
+        let name = match &self.kind {
+            ArgKind::Choice(choices) => choices.join("|"),
+            ArgKind::Rest => format!("{}...", self.name),
+            _ => self.name.to_string(),
+        };
+
+        if self.optional {
+            format!("[{}]", name)
+        } else {
+            format!("<{}>", name)
+        }
+    }
+}
+
+/// A `/name` command and the arguments it takes.
+#[derive(Debug, Clone)]
+pub struct CommandSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub args: Vec<ArgSpec>,
+}
+
+impl CommandSpec {
+    pub fn new(name: &'static str, description: &'static str) -> Self {
+        CommandSpec {
+            name,
+            description,
+            args: vec![],
+        }
+    }
+
+    pub fn arg(mut self, name: &'static str, kind: ArgKind) -> Self {
+        self.args.push(ArgSpec {
+            name,
+            kind,
+            optional: false,
+        });
+        self
+    }
+
+    /// Optional arguments go after the required ones.
+    pub fn optional_arg(mut self, name: &'static str, kind: ArgKind) -> Self {
+        self.args.push(ArgSpec {
+            name,
+            kind,
+            optional: true,
+        });
+        self
+    }
+
+    pub fn usage(&self) -> String {
+        std::iter::once(format!("/{}", self.name))
+            .chain(self.args.iter().map(ArgSpec::usage))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn parse(&self, rest: &str) -> Result<Vec<ArgValue>, String> {
+        let mut rest = rest.trim_start();
+        let mut values = vec![];
+
+        for arg in self.args.iter() {
+            if rest.is_empty() {
+                if arg.optional {
+                    break;
+                }
+                return Err(format!("missing {}", arg.usage()));
+            }
+
+            let word = match arg.kind {
+                ArgKind::Rest => std::mem::take(&mut rest).trim_end(),
+                _ => {
+                    let (word, tail) = rest.split_once(char::is_whitespace).unwrap_or((rest, ""));
+                    rest = tail.trim_start();
+                    word
+                }
+            };
+
+            let value = match &arg.kind {
+                ArgKind::Word | ArgKind::Rest => ArgValue::Word(word.to_string()),
+                ArgKind::Noun => match noun_slug(word) {
+                    Some(noun) => ArgValue::Word(noun),
+                    None => return Err(format!("{:?} is not a valid noun", word)),
+                },
+                ArgKind::Number => match word.parse() {
+                    Ok(number) => ArgValue::Number(number),
+                    Err(_) => {
+                        return Err(format!("{} should be a number, not {:?}", arg.name, word))
+                    }
+                },
+                ArgKind::Choice(choices) => {
+                    if !choices.contains(&word) {
+                        return Err(format!(
+                            "{} should be one of {}",
+                            arg.name,
+                            choices.join(", ")
+                        ));
+                    }
+                    ArgValue::Word(word.to_string())
+                }
+            };
+
+            values.push(value);
+        }
+
+        if !rest.is_empty() {
+            return Err(format!("unexpected {:?}", rest));
+        }
+
+        Ok(values)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ArgValue {
+    Word(String),
+    Number(f32),
+}
+
+/// Every command plugins have registered, by name.
+#[derive(Resource, Default)]
+pub struct CommandRegistry {
+    commands: BTreeMap<&'static str, CommandSpec>,
+}
+
+impl CommandRegistry {
+    pub fn register(&mut self, spec: CommandSpec) {
+        if self.commands.contains_key(spec.name) {
+            println!("Command /{} is registered twice", spec.name);
+        }
+
+        self.commands.insert(spec.name, spec);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&CommandSpec> {
+        self.commands.get(name)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &CommandSpec> {
+        self.commands.values()
+    }
+
+    /// Commands whose name starts with `prefix`.
+    pub fn completions<'a>(&'a self, prefix: &'a str) -> impl Iterator<Item = &'a CommandSpec> {
+        self.commands
+            .values()
+            .filter(move |spec| spec.name.starts_with(prefix))
+    }
+
+    /// Parses a line like `/spawnat cursor` into an invocation of a registered command.
+    pub fn parse(&self, line: &str) -> Result<CommandInvocation, String> {
+        let line = line.trim().trim_start_matches('/');
+        let (name, rest) = line.split_once(char::is_whitespace).unwrap_or((line, ""));
+
+        let Some(spec) = self.get(name) else {
+            return Err(format!("unknown command /{}, see /help", name));
+        };
+
+        let args = spec
+            .parse(rest)
+            .map_err(|err| format!("{}, usage: {}", err, spec.usage()))?;
+
+        Ok(CommandInvocation {
+            name: spec.name.to_string(),
+            args,
+        })
+    }
+}
+
+pub trait RegisterCommand {
+    /// Adds `spec` to the [`CommandRegistry`]; the plugin then handles its
+    /// [`CommandInvocation`]s.
+    fn register_command(&mut self, spec: CommandSpec) -> &mut Self;
+}
+
+impl RegisterCommand for App {
+    fn register_command(&mut self, spec: CommandSpec) -> &mut Self {
+        self.world
+            .get_resource_or_insert_with(CommandRegistry::default)
+            .register(spec);
+        self
+    }
+}
+
+/// A line starting with `/`, from the text input or elsewhere.
+#[derive(Event, Debug, Clone)]
+pub struct CommandLine(pub String);
+
+/// A parsed command with arguments matching its [`CommandSpec`].
+#[derive(Event, Debug, Clone)]
+pub struct CommandInvocation {
+    pub name: String,
+    pub args: Vec<ArgValue>,
+}
+
+impl CommandInvocation {
+    pub fn word(&self, index: usize) -> Option<&str> {
+        match self.args.get(index) {
+            Some(ArgValue::Word(word)) => Some(word.as_str()),
+            _ => None,
+        }
+    }
+
+    pub fn number(&self, index: usize) -> Option<f32> {
+        match self.args.get(index) {
+            Some(ArgValue::Number(number)) => Some(*number),
+            _ => None,
+        }
+    }
+}
+
+/// How a command went, for handlers to tell the player; shown as a [`Notification`].
+#[derive(SystemParam)]
+pub struct CommandFeedback<'w> {
+    notifications: EventWriter<'w, Notification>,
+}
+
+impl CommandFeedback<'_> {
+    pub fn send(&mut self, message: impl Into<String>) {
+        self.notifications.send(Notification::new(message));
+    }
+}
+
+fn parse_command_lines(
+    registry: Res<CommandRegistry>,
+    mut lines: EventReader<CommandLine>,
+    mut invocations: EventWriter<CommandInvocation>,
+    mut feedback: CommandFeedback,
+) {
+    for CommandLine(line) in lines.read() {
+        match registry.parse(line) {
+            Ok(invocation) => {
+                invocations.send(invocation);
+            }
+            Err(err) => feedback.send(err),
+        }
+    }
+}
+
+fn run_help_command(
+    registry: Res<CommandRegistry>,
+    mut invocations: EventReader<CommandInvocation>,
+    mut feedback: CommandFeedback,
+) {
+    for invocation in invocations.read() {
+        if invocation.name != "help" {
+            continue;
+        }
+
+        let help = match invocation.word(0) {
+            Some(name) => match registry.get(name.trim_start_matches('/')) {
+                Some(spec) => format!("{}\n{}", spec.usage(), spec.description),
+                None => format!("unknown command /{}", name),
+            },
+            None => registry
+                .iter()
+                .map(|spec| format!("{} - {}", spec.usage(), spec.description))
+                .collect::<Vec<_>>()
+                .join("\n"),
+        };
+
+        feedback.send(help);
+    }
+}
+
+/// Usage of the commands matching what is being typed.
+#[derive(Component)]
+struct CommandHint;
+
+fn spawn_command_hint(mut commands: Commands) {
+    commands.spawn((
+        CommandHint,
+        TextBundle::from_section(
+            "",
+            TextStyle {
+                font_size: HINT_FONT_SIZE,
+                color: HINT_COLOR,
+                ..default()
+            },
+        )
+        .with_style(Style {
+            position_type: PositionType::Absolute,
+            top: Val::Percent(55.0),
+            left: Val::Percent(40.0),
+            ..default()
+        }),
+    ));
+}
+
+fn despawn_command_hint(mut commands: Commands, hint_query: Query<Entity, With<CommandHint>>) {
+    for entity in hint_query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+/// Completes the command name on Tab, as far as it is unambiguous.
+fn autocomplete_command(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    registry: Res<CommandRegistry>,
+    mut input_query: Query<&mut TextInputValue>,
+) {
+    if !keyboard_input.just_pressed(KeyCode::Tab) {
+        return;
+    }
+
+    for mut value in input_query.iter_mut() {
+        let Some(prefix) = value.0.strip_prefix('/') else {
+            continue;
+        };
+        if prefix.contains(char::is_whitespace) {
+            continue;
+        }
+
+        let names: Vec<&str> = registry.completions(prefix).map(|spec| spec.name).collect();
+        let completed = match names.as_slice() {
+            [] => continue,
+            [name] => format!("/{} ", name),
+            [first, rest @ ..] => {
+                let common = rest.iter().fold(first.len(), |common, name| {
+                    first
+                        .bytes()
+                        .zip(name.bytes())
+                        .take(common)
+                        .take_while(|(a, b)| a == b)
+                        .count()
+                });
+                format!("/{}", &first[..common])
+            }
+        };
+
+        if completed != value.0 {
+            value.0 = completed;
+        }
+    }
+}
+
+fn update_command_hint(
+    registry: Res<CommandRegistry>,
+    input_query: Query<&TextInputValue, Changed<TextInputValue>>,
+    mut hint_query: Query<&mut Text, With<CommandHint>>,
+) {
+    let Ok(value) = input_query.get_single() else {
+        return;
+    };
+    let Ok(mut hint) = hint_query.get_single_mut() else {
+        return;
+    };
+
+    hint.sections[0].value = match value.0.strip_prefix('/') {
+        Some(line) => {
+            // once the name is typed, only that command is relevant
+            let hints: Vec<String> = match line.split_once(char::is_whitespace) {
+                Some((name, _)) => registry
+                    .get(name)
+                    .into_iter()
+                    .map(CommandSpec::usage)
+                    .collect(),
+                None => registry.completions(line).map(CommandSpec::usage).collect(),
+            };
+            hints.join("\n")
+        }
+        None => String::new(),
+    };
+}
@@ -0,0 +1,246 @@
+use std::fs::OpenOptions;
+
+use bevy::{prelude::*, scene::SceneInstance};
+use serde::{Deserialize, Serialize};
+
+use crate::components::noun::NounRegistry;
+
+use super::{
+    generator::{queue::GenerationQueue, Generator},
+    normalize::PendingNormalization,
+    notification::Notification,
+    ui::{
+        finish_mesh_loading, MeshLoading, MeshLoadingFailed, MeshLoadingTimeout, PLACEHOLDER_NOUN,
+    },
+};
+
+const CONFIG_PATH: &str = "assets/fallback.ron";
+
+pub struct GenerationFallbackPlugin;
+
+impl Plugin for GenerationFallbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(FallbackConfig::import()).add_systems(
+            Update,
+            (apply_generation_fallback, retry_generation).run_if(any_with_component::<MeshLoading>),
+        );
+    }
+}
+
+/// What to do with an object whose model could not be generated.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+pub enum FallbackStrategy {
+    /// Ask the generator again, waiting longer after every failure. Skipped while
+    /// generation is offline.
+    Retry,
+    /// Use the model of the closest spelled noun in `assets/models`.
+    NearestNoun,
+    /// Turn into a cube that keeps the requested color, material and size.
+    Primitive,
+    /// Remove the object.
+    Remove,
+}
+
+/// Read from `assets/fallback.ron`.
+#[derive(Resource, Debug, Serialize, Deserialize, Clone)]
+#[serde(default)]
+pub struct FallbackConfig {
+    /// Tried in order until one applies; `Retry` stops applying after `max_retries`
+    /// and `NearestNoun` when nothing is within `max_noun_distance`.
+    pub strategies: Vec<FallbackStrategy>,
+    pub max_retries: u32,
+    /// Seconds before the first retry, doubled for every retry after it.
+    pub retry_delay: f32,
+    /// Most single-letter edits between the requested noun and a stand-in.
+    pub max_noun_distance: usize,
+}
+
+impl Default for FallbackConfig {
+    fn default() -> Self {
+        FallbackConfig {
+            strategies: vec![
+                FallbackStrategy::Retry,
+                FallbackStrategy::NearestNoun,
+                FallbackStrategy::Primitive,
+            ],
+            max_retries: 2,
+            retry_delay: 5.0,
+            max_noun_distance: 3,
+        }
+    }
+}
+
+impl FallbackConfig {
+    pub fn import() -> Self {
+        let Ok(file) = OpenOptions::new().read(true).open(CONFIG_PATH) else {
+            return FallbackConfig::default();
+        };
+
+        match ron::de::from_reader(file) {
+            Ok(config) => config,
+            Err(err) => {
+                println!("Failed to read {}: {}", CONFIG_PATH, err);
+                FallbackConfig::default()
+            }
+        }
+    }
+}
+
+/// Counts down to the next generation attempt of a `MeshLoading` entity.
+#[derive(Component, Deref, DerefMut)]
+pub struct RetryGeneration(Timer);
+
+#[allow(clippy::too_many_arguments)]
+fn apply_generation_fallback(
+    time: Res<Time>,
+    mut commands: Commands,
+    config: Res<FallbackConfig>,
+    generator: Res<Generator>,
+    asset_server: Res<AssetServer>,
+    mut noun_registry: ResMut<NounRegistry>,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut notifications: EventWriter<Notification>,
+    mut query: Query<(Entity, &Transform, &mut MeshLoading, &mut MeshLoadingFailed)>,
+) {
+    for (entity, transform, mut mesh_loading, mut failed) in query.iter_mut() {
+        // leave the failure on screen for a moment first
+        if !failed.timer.tick(time.delta()).just_finished() {
+            continue;
+        }
+
+        let noun = mesh_loading.noun.clone();
+        let reason = failed.reason.clone();
+
+        let nearest = nearest_known_noun(&noun, config.max_noun_distance);
+
+        let applied = config.strategies.iter().find(|strategy| match strategy {
+            FallbackStrategy::Retry => {
+                !generator.is_offline() && mesh_loading.attempts < config.max_retries
+            }
+            FallbackStrategy::NearestNoun => nearest.is_some(),
+            FallbackStrategy::Primitive | FallbackStrategy::Remove => true,
+        });
+
+        match (applied, nearest) {
+            (Some(FallbackStrategy::Retry), _) => {
+                let delay = config.retry_delay * 2f32.powi(mesh_loading.attempts as i32);
+                mesh_loading.attempts += 1;
+
+                commands
+                    .entity(entity)
+                    .remove::<MeshLoadingFailed>()
+                    .insert(RetryGeneration(Timer::from_seconds(delay, TimerMode::Once)));
+                notifications.send(Notification(format!(
+                    "Generating {} failed ({}), retrying in {:.0}s",
+                    noun, reason, delay
+                )));
+            }
+            (Some(FallbackStrategy::NearestNoun), Some(known)) => {
+                finish_mesh_loading(
+                    &mut commands,
+                    entity,
+                    transform,
+                    &known,
+                    &asset_server,
+                    &mut noun_registry,
+                );
+                notifications.send(Notification(format!(
+                    "Generating {} failed ({}), using {} instead",
+                    noun, reason, known
+                )));
+            }
+            (Some(FallbackStrategy::Primitive), _) => {
+                // the placeholder collider is already a unit cube pivoted at the bottom
+                let mesh = Mesh::from(Cuboid::new(1.0, 1.0, 1.0)).translated_by(Vec3::Y * 0.5);
+
+                commands
+                    .entity(entity)
+                    .despawn_descendants()
+                    .remove::<(
+                        MeshLoading,
+                        MeshLoadingTimeout,
+                        MeshLoadingFailed,
+                        PendingNormalization,
+                        Handle<Scene>,
+                        SceneInstance,
+                    )>()
+                    .insert((meshes.add(mesh), mesh_loading.material.clone()));
+                notifications.send(Notification(format!(
+                    "Generating {} failed ({}), using a cube instead",
+                    noun, reason
+                )));
+            }
+            // `Remove`, or no strategy applied
+            _ => {
+                commands.entity(entity).despawn_recursive();
+                notifications.send(Notification(format!(
+                    "Generating {} failed ({})",
+                    noun, reason
+                )));
+            }
+        }
+    }
+}
+
+/// Sends the noun of every `RetryGeneration` entity back to the queue once its delay is up.
+fn retry_generation(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut queue: ResMut<GenerationQueue>,
+    mut query: Query<(Entity, &MeshLoading, &mut RetryGeneration)>,
+) {
+    for (entity, mesh_loading, mut retry) in query.iter_mut() {
+        if retry.tick(time.delta()).just_finished() {
+            // placeholders of the same noun share a single job
+            queue.enqueue(&mesh_loading.noun);
+
+            commands
+                .entity(entity)
+                .remove::<RetryGeneration>()
+                .insert(MeshLoadingTimeout::default());
+        }
+    }
+}
+
+/// The noun in `assets/models` that takes the fewest edits to reach from `noun`, or from
+/// any of its words, e.g. `chocolate_cake` finds `cake`.
+fn nearest_known_noun(noun: &str, max_distance: usize) -> Option<String> {
+    let entries = std::fs::read_dir("assets/models").ok()?;
+
+    entries
+        .filter_map(|entry| entry.ok())
+        .filter(|entry| entry.path().join("mesh.glb").exists())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        // never offered as a stand-in, it is what every loading object already looks like
+        .filter(|known| known != noun && known != PLACEHOLDER_NOUN)
+        .map(|known| {
+            let distance = std::iter::once(noun)
+                .chain(noun.split('_'))
+                .map(|word| edit_distance(word, &known))
+                .min()
+                .unwrap_or(usize::MAX);
+            (distance, known)
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .min()
+        .map(|(_, known)| known)
+}
+
+/// Levenshtein distance.
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, a) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+
+        for (j, b) in b.iter().enumerate() {
+            let substitution = diagonal + usize::from(a != *b);
+            diagonal = row[j + 1];
+            row[j + 1] = substitution.min(row[j] + 1).min(row[j + 1] + 1);
+        }
+    }
+
+    row[b.len()]
+}
@@ -0,0 +1,194 @@
+use std::{
+    path::Path,
+    sync::mpsc::Sender,
+    thread,
+    time::{Duration, Instant},
+};
+
+use reqwest::{blocking::Client, Url};
+use serde::{Deserialize, Serialize};
+
+use super::{
+    protocol::{GenerationRequest, GenerationResponse},
+    queue::JOB_TIMEOUT,
+    MeshGenerator,
+};
+
+pub fn default_poll_interval() -> f32 {
+    1.0
+}
+
+/// Body of `POST <endpoint>/jobs`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JobRequest {
+    pub noun: String,
+    pub prompt: String,
+}
+
+/// Response to `POST <endpoint>/jobs`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JobCreated {
+    pub id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum JobState {
+    Pending,
+    Running,
+    Done,
+    Failed,
+}
+
+/// Response to `GET <endpoint>/jobs/<id>`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct JobStatus {
+    pub state: JobState,
+    /// Between 0 and 1, if the server reports it.
+    pub progress: Option<f32>,
+    /// Where to download the `.glb` once `state` is `done`, relative to the endpoint.
+    pub mesh_url: Option<String>,
+    pub error: Option<String>,
+}
+
+pub struct HttpGenerator {
+    endpoint: String,
+    poll_interval: Duration,
+    client: Client,
+    responses: Sender<GenerationResponse>,
+}
+
+impl HttpGenerator {
+    pub fn new(endpoint: &str, poll_interval: f32, responses: Sender<GenerationResponse>) -> Self {
+        HttpGenerator {
+            // a trailing slash makes `Url::join` append instead of replacing the last segment
+            endpoint: format!("{}/", endpoint.trim_end_matches('/')),
+            poll_interval: Duration::from_secs_f32(poll_interval.max(0.1)),
+            client: Client::new(),
+            responses,
+        }
+    }
+}
+
+impl MeshGenerator for HttpGenerator {
+    fn request(&mut self, request: &GenerationRequest) -> bool {
+        let Ok(endpoint) = Url::parse(&self.endpoint) else {
+            println!("Invalid generation endpoint {}", self.endpoint);
+            return false;
+        };
+
+        let job = HttpJob {
+            endpoint,
+            poll_interval: self.poll_interval,
+            client: self.client.clone(),
+            request: request.clone(),
+            responses: self.responses.clone(),
+        };
+
+        // submitting, polling and downloading all block, so keep them off the frame
+        thread::spawn(move || {
+            let response = match job.run() {
+                Ok(path) => GenerationResponse::Success {
+                    id: job.request.id,
+                    path,
+                },
+                Err(reason) => GenerationResponse::Failure {
+                    id: job.request.id,
+                    reason,
+                },
+            };
+
+            let _ = job.responses.send(response);
+        });
+
+        true
+    }
+}
+
+struct HttpJob {
+    endpoint: Url,
+    poll_interval: Duration,
+    client: Client,
+    request: GenerationRequest,
+    responses: Sender<GenerationResponse>,
+}
+
+impl HttpJob {
+    /// Returns the path the model was saved to.
+    fn run(&self) -> Result<String, String> {
+        // the queue gives up on the job by then, so stop polling for it as well
+        let deadline = Instant::now() + Duration::from_secs_f32(JOB_TIMEOUT);
+
+        let created: JobCreated = self
+            .client
+            .post(self.url("jobs")?)
+            .json(&JobRequest {
+                noun: self.request.noun.clone(),
+                prompt: self.request.prompt.clone(),
+            })
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.json())
+            .map_err(|err| err.to_string())?;
+
+        let mesh_url = loop {
+            thread::sleep(self.poll_interval);
+            if Instant::now() > deadline {
+                return Err("timed out".to_string());
+            }
+
+            let status: JobStatus = self
+                .client
+                .get(self.url(&format!("jobs/{}", created.id))?)
+                .send()
+                .and_then(|response| response.error_for_status())
+                .and_then(|response| response.json())
+                .map_err(|err| err.to_string())?;
+
+            match status.state {
+                JobState::Pending | JobState::Running => {
+                    if let Some(progress) = status.progress {
+                        let _ = self.responses.send(GenerationResponse::Progress {
+                            id: self.request.id,
+                            progress,
+                            message: None,
+                        });
+                    }
+                }
+                JobState::Done => {
+                    break status
+                        .mesh_url
+                        .unwrap_or_else(|| format!("jobs/{}/mesh.glb", created.id))
+                }
+                JobState::Failed => {
+                    return Err(status.error.unwrap_or_else(|| "job failed".to_string()))
+                }
+            }
+        };
+
+        let bytes = self
+            .client
+            .get(self.url(&mesh_url)?)
+            .send()
+            .and_then(|response| response.error_for_status())
+            .and_then(|response| response.bytes())
+            .map_err(|err| err.to_string())?;
+
+        // write next to the final file and rename, so the game never sees half a model
+        let dir = Path::new("assets/models").join(&self.request.noun);
+        let part = dir.join("mesh.glb.part");
+        let path = dir.join("mesh.glb");
+        std::fs::create_dir_all(&dir)
+            .and_then(|_| std::fs::write(&part, bytes))
+            .and_then(|_| std::fs::rename(&part, &path))
+            .map_err(|err| err.to_string())?;
+
+        Ok(path.to_string_lossy().into_owned())
+    }
+
+    fn url(&self, path: &str) -> Result<Url, String> {
+        self.endpoint
+            .join(path.trim_start_matches('/'))
+            .map_err(|err| err.to_string())
+    }
+}
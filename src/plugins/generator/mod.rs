@@ -0,0 +1,227 @@
+pub mod http;
+pub mod pipe;
+pub mod procedural;
+pub mod protocol;
+pub mod queue;
+pub mod watcher;
+
+use std::{
+    collections::HashMap,
+    fs::OpenOptions,
+    sync::{
+        mpsc::{self, Receiver, Sender},
+        Mutex,
+    },
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use self::{
+    http::HttpGenerator,
+    pipe::{PipeGenerator, PipeProtocol},
+    procedural::ProceduralGenerator,
+    protocol::{GenerationRequest, GenerationResponse},
+    queue::{
+        dispatch_generation_queue, track_generation_responses, GenerationQueue, GenerationUpdate,
+    },
+    watcher::{forward_model_arrivals, setup_model_watcher},
+};
+
+const CONFIG_PATH: &str = "assets/generator.ron";
+
+pub struct GeneratorPlugin;
+
+impl Plugin for GeneratorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<GenerationResponse>()
+            .add_event::<GenerationUpdate>()
+            .init_resource::<GenerationQueue>()
+            .add_systems(Startup, (setup_generator, setup_model_watcher).chain())
+            .add_systems(
+                Update,
+                (
+                    forward_generator_responses,
+                    track_generation_responses,
+                    forward_model_arrivals,
+                    dispatch_generation_queue,
+                )
+                    .chain(),
+            );
+    }
+}
+
+/// Backend selection, read from `assets/generator.ron`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+pub enum GeneratorConfig {
+    /// Never generates anything; unknown nouns fall back to a cube.
+    #[default]
+    Offline,
+    /// Spawns `program` and talks to it over stdin and stdout, see [`PipeProtocol`].
+    Subprocess {
+        program: String,
+        args: Vec<String>,
+        current_dir: Option<String>,
+        #[serde(default)]
+        protocol: PipeProtocol,
+        /// Passed along with every request when speaking [`PipeProtocol::Json`].
+        #[serde(default)]
+        options: HashMap<String, String>,
+    },
+    /// Submits a job to the generation server at `endpoint`, polls it every
+    /// `poll_interval` seconds and downloads the finished `.glb`.
+    Http {
+        endpoint: String,
+        #[serde(default = "http::default_poll_interval")]
+        poll_interval: f32,
+    },
+    /// Builds models out of primitives picked from a hash of the noun, after
+    /// pretending to work for `delay` seconds.
+    Procedural {
+        #[serde(default = "procedural::default_delay")]
+        delay: f32,
+    },
+}
+
+impl GeneratorConfig {
+    pub fn import() -> Self {
+        let Ok(file) = OpenOptions::new().read(true).open(CONFIG_PATH) else {
+            println!("No {} found, mesh generation is offline", CONFIG_PATH);
+            return GeneratorConfig::Offline;
+        };
+
+        match ron::de::from_reader(file) {
+            Ok(config) => config,
+            Err(err) => {
+                println!("Failed to read {}: {}", CONFIG_PATH, err);
+                GeneratorConfig::Offline
+            }
+        }
+    }
+
+    pub fn build(&self, responses: Sender<GenerationResponse>) -> Box<dyn MeshGenerator> {
+        match self {
+            GeneratorConfig::Offline => Box::new(OfflineGenerator),
+            GeneratorConfig::Subprocess {
+                program,
+                args,
+                current_dir,
+                protocol,
+                options,
+            } => match PipeGenerator::spawn(
+                program,
+                args,
+                current_dir.as_deref(),
+                *protocol,
+                options.clone(),
+                responses,
+            ) {
+                Ok(generator) => Box::new(generator),
+                Err(err) => {
+                    println!(
+                        "Failed to start {}: {}, mesh generation is offline",
+                        program, err
+                    );
+                    Box::new(OfflineGenerator)
+                }
+            },
+            GeneratorConfig::Http {
+                endpoint,
+                poll_interval,
+            } => Box::new(HttpGenerator::new(endpoint, *poll_interval, responses)),
+            GeneratorConfig::Procedural { delay } => {
+                Box::new(ProceduralGenerator::new(*delay, responses))
+            }
+        }
+    }
+}
+
+impl GeneratorConfig {
+    /// Whether `mesh.glb` is written in place, with neither a rename nor a `ready` marker.
+    pub fn writes_in_place(&self) -> bool {
+        matches!(
+            self,
+            GeneratorConfig::Subprocess {
+                protocol: PipeProtocol::Lines,
+                ..
+            }
+        )
+    }
+}
+
+/// Something that eventually writes `assets/models/<noun>/mesh.glb`, following the
+/// rename or marker convention described on [`watcher::ModelWatcher`].
+pub trait MeshGenerator: Send + Sync {
+    /// Queues a model. Returns false if the backend cannot take the request.
+    fn request(&mut self, request: &GenerationRequest) -> bool;
+
+    /// Whether requests are refused outright, so there is no point in waiting for a model.
+    fn is_offline(&self) -> bool {
+        false
+    }
+}
+
+#[derive(Resource)]
+pub struct Generator {
+    config: GeneratorConfig,
+    backend: Box<dyn MeshGenerator>,
+    responses: Mutex<Receiver<GenerationResponse>>,
+    next_id: u64,
+}
+
+impl Generator {
+    pub fn new(config: &GeneratorConfig) -> Self {
+        let (sender, receiver) = mpsc::channel();
+
+        Generator {
+            config: config.clone(),
+            backend: config.build(sender),
+            responses: Mutex::new(receiver),
+            next_id: 0,
+        }
+    }
+
+    /// Asks the backend for a model of `noun` and returns the request id.
+    pub fn request(&mut self, noun: &str) -> Option<u64> {
+        let request = GenerationRequest::new(self.next_id, noun);
+
+        if self.backend.request(&request) {
+            self.next_id += 1;
+            Some(request.id)
+        } else {
+            None
+        }
+    }
+
+    pub fn is_offline(&self) -> bool {
+        self.backend.is_offline()
+    }
+
+    pub fn config(&self) -> &GeneratorConfig {
+        &self.config
+    }
+}
+
+fn setup_generator(mut commands: Commands) {
+    commands.insert_resource(Generator::new(&GeneratorConfig::import()));
+}
+
+fn forward_generator_responses(
+    generator: Res<Generator>,
+    mut events: EventWriter<GenerationResponse>,
+) {
+    let responses = generator.responses.lock().unwrap();
+    events.send_batch(responses.try_iter());
+}
+
+pub struct OfflineGenerator;
+
+impl MeshGenerator for OfflineGenerator {
+    fn request(&mut self, _request: &GenerationRequest) -> bool {
+        false
+    }
+
+    fn is_offline(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,105 @@
+use std::{
+    collections::HashMap,
+    io::{BufRead, BufReader, Write},
+    process::{Child, ChildStdin, Command, Stdio},
+    sync::mpsc::Sender,
+    thread,
+};
+
+use serde::{Deserialize, Serialize};
+
+use super::{
+    protocol::{GenerationRequest, GenerationResponse},
+    MeshGenerator,
+};
+
+/// How requests and results are exchanged with the generator process.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PipeProtocol {
+    /// A bare noun per line on stdin; stdout is ignored and results are only found on disk,
+    /// where `mesh.glb` may be written in place, see [`super::watcher::ModelWatcher`].
+    #[default]
+    Lines,
+    /// A [`GenerationRequest`] per line on stdin and a [`GenerationResponse`] per line on stdout.
+    Json,
+}
+
+pub struct PipeGenerator {
+    // kept so the process is owned by the game
+    _child: Child,
+    stdin: ChildStdin,
+    protocol: PipeProtocol,
+    options: HashMap<String, String>,
+}
+
+impl PipeGenerator {
+    pub fn spawn(
+        program: &str,
+        args: &[String],
+        current_dir: Option<&str>,
+        protocol: PipeProtocol,
+        options: HashMap<String, String>,
+        responses: Sender<GenerationResponse>,
+    ) -> std::io::Result<Self> {
+        let mut command = Command::new(program);
+        command.args(args).stdin(Stdio::piped());
+
+        match protocol {
+            PipeProtocol::Lines => command.stdout(Stdio::null()),
+            PipeProtocol::Json => command.stdout(Stdio::piped()),
+        };
+
+        if let Some(dir) = current_dir {
+            command.current_dir(dir);
+        }
+
+        let mut child = command.spawn()?;
+        let stdin = child.stdin.take().unwrap();
+
+        if let Some(stdout) = child.stdout.take() {
+            thread::spawn(move || {
+                for line in BufReader::new(stdout).lines() {
+                    let Ok(line) = line else {
+                        break;
+                    };
+
+                    match serde_json::from_str::<GenerationResponse>(&line) {
+                        Ok(response) => {
+                            if responses.send(response).is_err() {
+                                break;
+                            }
+                        }
+                        // anything else is the generator's own logging
+                        Err(_) => println!("[generator] {}", line),
+                    }
+                }
+            });
+        }
+
+        Ok(PipeGenerator {
+            _child: child,
+            stdin,
+            protocol,
+            options,
+        })
+    }
+}
+
+impl MeshGenerator for PipeGenerator {
+    fn request(&mut self, request: &GenerationRequest) -> bool {
+        match self.protocol {
+            PipeProtocol::Lines => writeln!(self.stdin, "{}", request.noun).is_ok(),
+            PipeProtocol::Json => {
+                let request = GenerationRequest {
+                    options: self.options.clone(),
+                    ..request.clone()
+                };
+
+                match serde_json::to_string(&request) {
+                    Ok(line) => writeln!(self.stdin, "{}", line).is_ok(),
+                    Err(_) => false,
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,430 @@
+use std::{f32::consts::TAU, io, path::Path, sync::mpsc::Sender, thread, time::Duration};
+
+use bevy::prelude::*;
+use rand::{rngs::StdRng, Rng, SeedableRng};
+use serde_json::json;
+
+use crate::components::noun::NounEntry;
+
+use super::{
+    protocol::{GenerationRequest, GenerationResponse},
+    MeshGenerator,
+};
+
+/// Progress steps reported while pretending to work.
+const STEPS: u32 = 5;
+
+pub fn default_delay() -> f32 {
+    2.0
+}
+
+/// Builds a model out of boxes, cylinders and spheres picked from a hash of the noun,
+/// so the same noun always gets the same model. Needs no GPU or network.
+pub struct ProceduralGenerator {
+    delay: Duration,
+    responses: Sender<GenerationResponse>,
+}
+
+impl ProceduralGenerator {
+    pub fn new(delay: f32, responses: Sender<GenerationResponse>) -> Self {
+        ProceduralGenerator {
+            delay: Duration::from_secs_f32(delay.max(0.0)),
+            responses,
+        }
+    }
+}
+
+impl MeshGenerator for ProceduralGenerator {
+    fn request(&mut self, request: &GenerationRequest) -> bool {
+        let request = request.clone();
+        let delay = self.delay;
+        let responses = self.responses.clone();
+
+        thread::spawn(move || {
+            for step in 0..STEPS {
+                thread::sleep(delay / STEPS);
+                let _ = responses.send(GenerationResponse::Progress {
+                    id: request.id,
+                    progress: step as f32 / STEPS as f32,
+                    message: None,
+                });
+            }
+
+            let response = match generate(&request.noun) {
+                Ok(path) => GenerationResponse::Success {
+                    id: request.id,
+                    path,
+                },
+                Err(err) => GenerationResponse::Failure {
+                    id: request.id,
+                    reason: err.to_string(),
+                },
+            };
+
+            let _ = responses.send(response);
+        });
+
+        true
+    }
+}
+
+/// Writes `assets/models/<noun>/mesh.glb` and returns its path.
+pub fn generate(noun: &str) -> io::Result<String> {
+    let shape = ProceduralShape::from_noun(noun);
+
+    // write next to the final file and rename, like every other backend
+    let dir = Path::new("assets/models").join(noun);
+    let part = dir.join("mesh.glb.part");
+    let path = dir.join("mesh.glb");
+    std::fs::create_dir_all(&dir)?;
+    std::fs::write(&part, shape.to_glb(noun))?;
+    std::fs::rename(&part, &path)?;
+
+    Ok(path.to_string_lossy().into_owned())
+}
+
+/// FNV-1a, stable across runs and platforms unlike `DefaultHasher`.
+fn hash_noun(noun: &str) -> u64 {
+    noun.bytes().fold(0xcbf29ce484222325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+#[derive(Default)]
+struct ProceduralShape {
+    positions: Vec<[f32; 3]>,
+    normals: Vec<[f32; 3]>,
+    colors: Vec<[f32; 4]>,
+    indices: Vec<u32>,
+}
+
+impl ProceduralShape {
+    fn from_noun(noun: &str) -> Self {
+        let mut rng = StdRng::seed_from_u64(hash_noun(noun));
+        let mut shape = ProceduralShape::default();
+
+        let hue = rng.gen_range(0.0..360.0);
+        let body = Vec3::new(
+            rng.gen_range(0.4..1.0),
+            rng.gen_range(0.4..1.0),
+            rng.gen_range(0.4..1.0),
+        );
+        let body_color = Color::hsl(hue, 0.6, 0.5);
+        shape.add_part(
+            Primitive::Cuboid,
+            Transform::from_scale(body).with_translation(Vec3::Y * body.y / 2.0),
+            body_color,
+        );
+
+        // limbs, heads and bumps stuck onto the surface of the body
+        for _ in 0..rng.gen_range(2..7) {
+            let primitive = match rng.gen_range(0..3) {
+                0 => Primitive::Cuboid,
+                1 => Primitive::Cylinder,
+                _ => Primitive::Sphere,
+            };
+            let size = Vec3::new(
+                rng.gen_range(0.1..0.5),
+                rng.gen_range(0.1..0.6),
+                rng.gen_range(0.1..0.5),
+            );
+            let anchor = Vec3::new(
+                rng.gen_range(-0.5..0.5) * body.x,
+                rng.gen_range(0.0..1.0) * body.y,
+                rng.gen_range(-0.5..0.5) * body.z,
+            );
+            let rotation = Quat::from_rotation_z(rng.gen_range(-0.8..0.8))
+                * Quat::from_rotation_y(rng.gen_range(0.0..TAU));
+            let color = Color::hsl(
+                (hue + rng.gen_range(-40.0..40.0)).rem_euclid(360.0),
+                0.6,
+                rng.gen_range(0.3..0.7),
+            );
+
+            shape.add_part(
+                primitive,
+                Transform::from_translation(anchor)
+                    .with_rotation(rotation)
+                    .with_scale(size),
+                color,
+            );
+        }
+
+        // lay the model down the way image-to-3D models come out, so the default
+        // noun orientation stands it back up
+        let orientation = NounEntry::default().orientation().inverse();
+        for position in shape.positions.iter_mut() {
+            *position = (orientation * Vec3::from(*position)).into();
+        }
+        for normal in shape.normals.iter_mut() {
+            *normal = (orientation * Vec3::from(*normal)).into();
+        }
+
+        shape
+    }
+
+    /// Appends a unit `primitive` moved by `transform`.
+    fn add_part(&mut self, primitive: Primitive, transform: Transform, color: Color) {
+        let mesh = match primitive {
+            Primitive::Cuboid => Mesh::from(Cuboid::new(1.0, 1.0, 1.0)),
+            Primitive::Cylinder => Mesh::from(Cylinder::new(0.5, 1.0)),
+            Primitive::Sphere => Mesh::from(Sphere::new(0.5)),
+        }
+        .transformed_by(transform);
+
+        let offset = self.positions.len() as u32;
+
+        let Some(positions) = mesh
+            .attribute(Mesh::ATTRIBUTE_POSITION)
+            .and_then(|positions| positions.as_float3())
+        else {
+            return;
+        };
+        let Some(normals) = mesh
+            .attribute(Mesh::ATTRIBUTE_NORMAL)
+            .and_then(|normals| normals.as_float3())
+        else {
+            return;
+        };
+
+        self.positions.extend_from_slice(positions);
+        self.normals.extend_from_slice(normals);
+        self.colors
+            .extend(vec![color.as_linear_rgba_f32(); positions.len()]);
+
+        if let Some(indices) = mesh.indices() {
+            self.indices
+                .extend(indices.iter().map(|i| i as u32 + offset));
+        }
+    }
+
+    /// Binary glTF with a single mesh, colored per vertex.
+    fn to_glb(&self, name: &str) -> Vec<u8> {
+        let mut buffer = Vec::new();
+        let mut views = Vec::new();
+
+        let mut push_view = |bytes: Vec<u8>, target: u32| {
+            views.push(json!({
+                "buffer": 0,
+                "byteOffset": buffer.len(),
+                "byteLength": bytes.len(),
+                "target": target,
+            }));
+            buffer.extend(bytes);
+        };
+
+        const ARRAY_BUFFER: u32 = 34962;
+        const ELEMENT_ARRAY_BUFFER: u32 = 34963;
+        push_view(floats(self.positions.iter().flatten()), ARRAY_BUFFER);
+        push_view(floats(self.normals.iter().flatten()), ARRAY_BUFFER);
+        push_view(floats(self.colors.iter().flatten()), ARRAY_BUFFER);
+        push_view(
+            self.indices.iter().flat_map(|i| i.to_le_bytes()).collect(),
+            ELEMENT_ARRAY_BUFFER,
+        );
+
+        let min = self
+            .positions
+            .iter()
+            .fold(Vec3::MAX, |min, &p| min.min(Vec3::from(p)));
+        let max = self
+            .positions
+            .iter()
+            .fold(Vec3::MIN, |max, &p| max.max(Vec3::from(p)));
+
+        const FLOAT: u32 = 5126;
+        const UNSIGNED_INT: u32 = 5125;
+        let document = json!({
+            "asset": { "version": "2.0", "generator": "scrb procedural generator" },
+            "scene": 0,
+            "scenes": [{ "nodes": [0] }],
+            "nodes": [{ "mesh": 0, "name": name }],
+            "meshes": [{
+                "name": name,
+                "primitives": [{
+                    "attributes": { "POSITION": 0, "NORMAL": 1, "COLOR_0": 2 },
+                    "indices": 3,
+                    "material": 0,
+                }],
+            }],
+            "materials": [{
+                "pbrMetallicRoughness": {
+                    "baseColorFactor": [1.0, 1.0, 1.0, 1.0],
+                    "metallicFactor": 0.0,
+                    "roughnessFactor": 0.8,
+                },
+            }],
+            "buffers": [{ "byteLength": buffer.len() }],
+            "bufferViews": views,
+            "accessors": [
+                {
+                    "bufferView": 0,
+                    "componentType": FLOAT,
+                    "count": self.positions.len(),
+                    "type": "VEC3",
+                    "min": min.to_array(),
+                    "max": max.to_array(),
+                },
+                {
+                    "bufferView": 1,
+                    "componentType": FLOAT,
+                    "count": self.normals.len(),
+                    "type": "VEC3",
+                },
+                {
+                    "bufferView": 2,
+                    "componentType": FLOAT,
+                    "count": self.colors.len(),
+                    "type": "VEC4",
+                },
+                {
+                    "bufferView": 3,
+                    "componentType": UNSIGNED_INT,
+                    "count": self.indices.len(),
+                    "type": "SCALAR",
+                },
+            ],
+        });
+
+        glb(document.to_string().into_bytes(), buffer)
+    }
+}
+
+enum Primitive {
+    Cuboid,
+    Cylinder,
+    Sphere,
+}
+
+fn floats<'a>(values: impl Iterator<Item = &'a f32>) -> Vec<u8> {
+    values.flat_map(|value| value.to_le_bytes()).collect()
+}
+
+/// Packs a glTF document and its buffer into the binary container.
+fn glb(mut json: Vec<u8>, mut bin: Vec<u8>) -> Vec<u8> {
+    // chunks are 4-byte aligned, JSON padded with spaces and BIN with zeros
+    json.resize(json.len().next_multiple_of(4), b' ');
+    bin.resize(bin.len().next_multiple_of(4), 0);
+
+    let length = 12 + 8 + json.len() + 8 + bin.len();
+
+    let mut glb = Vec::with_capacity(length);
+    glb.extend_from_slice(b"glTF");
+    glb.extend_from_slice(&2u32.to_le_bytes());
+    glb.extend_from_slice(&(length as u32).to_le_bytes());
+
+    glb.extend_from_slice(&(json.len() as u32).to_le_bytes());
+    glb.extend_from_slice(b"JSON");
+    glb.extend(json);
+
+    glb.extend_from_slice(&(bin.len() as u32).to_le_bytes());
+    glb.extend_from_slice(b"BIN\0");
+    glb.extend(bin);
+
+    glb
+}
+
+/// Removes the model a test generated once it is dropped, so the test leaves
+/// nothing behind in `assets/models` even when it fails.
+#[cfg(test)]
+pub(crate) struct TestModel(pub &'static str);
+
+#[cfg(test)]
+impl Drop for TestModel {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(Path::new("assets/models").join(self.0));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::mpsc;
+
+    use super::*;
+
+    fn chunk_length(glb: &[u8], offset: usize) -> usize {
+        u32::from_le_bytes(glb[offset..offset + 4].try_into().unwrap()) as usize
+    }
+
+    #[test]
+    fn same_noun_same_model() {
+        let first = ProceduralShape::from_noun("cake").to_glb("cake");
+        let second = ProceduralShape::from_noun("cake").to_glb("cake");
+
+        assert_eq!(first, second);
+    }
+
+    #[test]
+    fn nouns_get_different_models() {
+        let cake = ProceduralShape::from_noun("cake");
+        let dinosaur = ProceduralShape::from_noun("dinosaur");
+
+        assert_ne!(cake.positions, dinosaur.positions);
+    }
+
+    #[test]
+    fn hash_is_fnv_1a() {
+        assert_eq!(hash_noun(""), 0xcbf29ce484222325);
+        assert_eq!(hash_noun("a"), 0xaf63dc4c8601ec8c);
+    }
+
+    #[test]
+    fn glb_chunks_match_the_document() {
+        let shape = ProceduralShape::from_noun("king_cobra");
+        let glb = shape.to_glb("king_cobra");
+
+        assert_eq!(&glb[0..4], b"glTF");
+        assert_eq!(chunk_length(&glb, 8), glb.len());
+
+        let json_length = chunk_length(&glb, 12);
+        assert_eq!(&glb[16..20], b"JSON");
+        let document: serde_json::Value =
+            serde_json::from_slice(&glb[20..20 + json_length]).unwrap();
+
+        let bin = 20 + json_length;
+        assert_eq!(&glb[bin + 4..bin + 8], b"BIN\0");
+        assert_eq!(
+            chunk_length(&glb, bin),
+            document["buffers"][0]["byteLength"]
+                .as_u64()
+                .unwrap()
+                .next_multiple_of(4) as usize
+        );
+
+        let accessors = &document["accessors"];
+        assert_eq!(accessors[0]["count"], shape.positions.len());
+        assert_eq!(accessors[3]["count"], shape.indices.len());
+        assert!(shape
+            .indices
+            .iter()
+            .all(|&i| (i as usize) < shape.positions.len()));
+    }
+
+    #[test]
+    fn request_writes_the_model_and_reports_success() {
+        let model = TestModel("procedural_request_test");
+        let noun = model.0;
+        let (sender, receiver) = mpsc::channel();
+        let mut generator = ProceduralGenerator::new(0.0, sender);
+
+        assert!(generator.request(&GenerationRequest::new(7, noun)));
+        drop(generator);
+
+        // the worker thread drops its sender once it is done
+        let responses: Vec<GenerationResponse> = receiver.iter().collect();
+        let written = Path::new("assets/models").join(noun).join("mesh.glb");
+
+        assert_eq!(responses.len(), STEPS as usize + 1);
+        assert_eq!(
+            responses.last(),
+            Some(&GenerationResponse::Success {
+                id: 7,
+                path: written.to_string_lossy().into_owned(),
+            })
+        );
+        assert_eq!(
+            std::fs::read(&written).ok(),
+            Some(ProceduralShape::from_noun(noun).to_glb(noun))
+        );
+    }
+}
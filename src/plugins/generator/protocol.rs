@@ -0,0 +1,58 @@
+use std::collections::HashMap;
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+/// One line sent to a generator, e.g.
+/// `{"id":3,"noun":"king_cobra","prompt":"king cobra","options":{}}`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct GenerationRequest {
+    pub id: u64,
+    pub noun: String,
+    pub prompt: String,
+    #[serde(default)]
+    pub options: HashMap<String, String>,
+}
+
+impl GenerationRequest {
+    pub fn new(id: u64, noun: &str) -> Self {
+        GenerationRequest {
+            id,
+            noun: noun.to_string(),
+            prompt: noun.replace('_', " "),
+            options: HashMap::new(),
+        }
+    }
+}
+
+/// One line received from a generator, e.g.
+/// `{"type":"success","id":3,"path":"assets/models/king_cobra/mesh.glb"}`.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Event)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum GenerationResponse {
+    Progress {
+        id: u64,
+        /// Between 0 and 1.
+        progress: f32,
+        #[serde(default)]
+        message: Option<String>,
+    },
+    Success {
+        id: u64,
+        path: String,
+    },
+    Failure {
+        id: u64,
+        reason: String,
+    },
+}
+
+impl GenerationResponse {
+    pub fn id(&self) -> u64 {
+        match *self {
+            GenerationResponse::Progress { id, .. }
+            | GenerationResponse::Success { id, .. }
+            | GenerationResponse::Failure { id, .. } => id,
+        }
+    }
+}
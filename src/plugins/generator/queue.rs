@@ -0,0 +1,175 @@
+use std::collections::{HashMap, VecDeque};
+
+use bevy::prelude::*;
+
+use crate::components::noun::is_noun_slug;
+
+use super::{protocol::GenerationResponse, Generator};
+
+const MAX_CONCURRENT_JOBS: usize = 2;
+/// Jobs that never report back (or never write their file) stop holding a slot after this.
+pub const JOB_TIMEOUT: f32 = 120.0;
+
+/// What happened to the model requested for a noun, for every entity waiting on it.
+#[derive(Event, Debug, Clone, PartialEq)]
+pub enum GenerationUpdate {
+    Progress { noun: String, progress: f32 },
+    Finished { noun: String },
+    Failed { noun: String, reason: String },
+}
+
+struct InFlightJob {
+    id: u64,
+    started: f32,
+}
+
+/// Nouns waiting for a model. Each noun is generated once no matter how many
+/// objects are waiting for it, and at most `max_concurrent` jobs run at a time.
+#[derive(Resource)]
+pub struct GenerationQueue {
+    pub max_concurrent: usize,
+    pending: VecDeque<String>,
+    in_flight: HashMap<String, InFlightJob>,
+}
+
+impl Default for GenerationQueue {
+    fn default() -> Self {
+        GenerationQueue {
+            max_concurrent: MAX_CONCURRENT_JOBS,
+            pending: VecDeque::new(),
+            in_flight: HashMap::new(),
+        }
+    }
+}
+
+impl GenerationQueue {
+    /// Queues `noun` unless it is already pending or being generated.
+    pub fn enqueue(&mut self, noun: &str) {
+        // nouns become folder names and generator input
+        if !is_noun_slug(noun) {
+            println!("Refusing to generate invalid noun {:?}", noun);
+            return;
+        }
+
+        if !self.contains(noun) {
+            self.pending.push_back(noun.to_string());
+        }
+    }
+
+    pub fn contains(&self, noun: &str) -> bool {
+        self.is_in_flight(noun) || self.pending.iter().any(|n| n == noun)
+    }
+
+    pub fn is_in_flight(&self, noun: &str) -> bool {
+        self.in_flight.contains_key(noun)
+    }
+
+    /// Drops a job that has not been sent to the backend yet.
+    pub fn cancel(&mut self, noun: &str) -> bool {
+        let before = self.pending.len();
+        self.pending.retain(|n| n != noun);
+        self.pending.len() != before
+    }
+
+    /// Frees the slot of a job, whether or not its model arrived.
+    pub fn finish(&mut self, noun: &str) {
+        self.in_flight.remove(noun);
+    }
+
+    pub fn pending(&self) -> impl Iterator<Item = &String> {
+        self.pending.iter()
+    }
+
+    pub fn in_flight(&self) -> impl Iterator<Item = &String> {
+        self.in_flight.keys()
+    }
+
+    fn noun_for(&self, id: u64) -> Option<String> {
+        self.in_flight
+            .iter()
+            .find(|(_, job)| job.id == id)
+            .map(|(noun, _)| noun.clone())
+    }
+}
+
+/// Resolves backend responses to nouns and frees the slots of finished jobs.
+pub fn track_generation_responses(
+    time: Res<Time>,
+    mut queue: ResMut<GenerationQueue>,
+    mut responses: EventReader<GenerationResponse>,
+    mut updates: EventWriter<GenerationUpdate>,
+) {
+    for response in responses.read() {
+        let Some(noun) = queue.noun_for(response.id()) else {
+            continue;
+        };
+
+        match response {
+            GenerationResponse::Progress { progress, .. } => {
+                updates.send(GenerationUpdate::Progress {
+                    noun,
+                    progress: *progress,
+                });
+            }
+            GenerationResponse::Success { .. } => {
+                queue.finish(&noun);
+                updates.send(GenerationUpdate::Finished { noun });
+            }
+            GenerationResponse::Failure { reason, .. } => {
+                queue.finish(&noun);
+                updates.send(GenerationUpdate::Failed {
+                    noun,
+                    reason: reason.clone(),
+                });
+            }
+        }
+    }
+
+    let now = time.elapsed_seconds();
+    let expired: Vec<String> = queue
+        .in_flight
+        .iter()
+        .filter(|(_, job)| now - job.started > JOB_TIMEOUT)
+        .map(|(noun, _)| noun.clone())
+        .collect();
+
+    for noun in expired {
+        queue.finish(&noun);
+        updates.send(GenerationUpdate::Failed {
+            noun,
+            reason: "timed out".to_string(),
+        });
+    }
+}
+
+/// Sends pending nouns to the backend while there are free slots.
+pub fn dispatch_generation_queue(
+    time: Res<Time>,
+    mut queue: ResMut<GenerationQueue>,
+    mut generator: ResMut<Generator>,
+    mut updates: EventWriter<GenerationUpdate>,
+) {
+    while queue.in_flight.len() < queue.max_concurrent {
+        let Some(noun) = queue.pending.pop_front() else {
+            break;
+        };
+
+        match generator.request(&noun) {
+            Some(id) => {
+                queue.in_flight.insert(
+                    noun,
+                    InFlightJob {
+                        id,
+                        started: time.elapsed_seconds(),
+                    },
+                );
+            }
+            None => {
+                updates.send(GenerationUpdate::Failed {
+                    noun,
+                    reason: "the generator did not accept the request".to_string(),
+                });
+            }
+        }
+    }
+}
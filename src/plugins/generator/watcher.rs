@@ -0,0 +1,135 @@
+use std::{
+    path::Path,
+    sync::{
+        mpsc::{self, Receiver},
+        Mutex,
+    },
+    time::Duration,
+};
+
+use bevy::prelude::*;
+use notify::{
+    event::{AccessKind, AccessMode, CreateKind, ModifyKind},
+    EventKind, RecommendedWatcher, RecursiveMode, Watcher,
+};
+
+use super::{
+    queue::{GenerationQueue, GenerationUpdate},
+    Generator,
+};
+
+const MODELS_DIR: &str = "assets/models";
+/// How often in-flight jobs are checked for a `mesh.glb` written in place.
+const POLL_INTERVAL: f32 = 1.0;
+/// A `mesh.glb` written in place counts as finished once untouched for this long.
+const SETTLE_TIME: Duration = Duration::from_secs(1);
+
+/// Watches `assets/models` for finished models.
+///
+/// A model for `<noun>` counts as arrived when either
+/// - `<noun>/mesh.glb` is renamed into place, e.g. from `<noun>/mesh.glb.part`, or
+/// - a `<noun>/ready` marker is created after `mesh.glb` was written in place.
+///
+/// Otherwise creating or writing `mesh.glb` is ignored, so a half-written file is never
+/// loaded. Backends that write it in place with neither, like [`super::pipe::PipeProtocol::Lines`],
+/// finish once the file is closed after writing, or, where that is not reported, once it
+/// has not changed for a second.
+#[derive(Resource)]
+pub struct ModelWatcher {
+    // dropping the watcher stops the notifications
+    _watcher: Mutex<RecommendedWatcher>,
+    arrivals: Mutex<Receiver<String>>,
+    in_place: bool,
+}
+
+impl ModelWatcher {
+    pub fn new(in_place: bool) -> notify::Result<Self> {
+        std::fs::create_dir_all(MODELS_DIR)?;
+
+        let (sender, receiver) = mpsc::channel();
+
+        let mut watcher =
+            notify::recommended_watcher(move |event: notify::Result<notify::Event>| {
+                let Ok(event) = event else {
+                    return;
+                };
+
+                for path in &event.paths {
+                    if let Some(noun) = arrived_noun(&event.kind, path, in_place) {
+                        let _ = sender.send(noun);
+                    }
+                }
+            })?;
+        watcher.watch(Path::new(MODELS_DIR), RecursiveMode::Recursive)?;
+
+        Ok(ModelWatcher {
+            _watcher: Mutex::new(watcher),
+            arrivals: Mutex::new(receiver),
+            in_place,
+        })
+    }
+}
+
+fn arrived_noun(kind: &EventKind, path: &Path, in_place: bool) -> Option<String> {
+    let file_name = path.file_name()?.to_str()?;
+
+    let ready = match kind {
+        EventKind::Modify(ModifyKind::Name(_)) => file_name == "mesh.glb",
+        EventKind::Create(CreateKind::File | CreateKind::Any) => file_name == "ready",
+        EventKind::Access(AccessKind::Close(AccessMode::Write)) => {
+            in_place && file_name == "mesh.glb"
+        }
+        _ => false,
+    };
+
+    // renaming away from `mesh.glb` also reports its old path
+    if !ready || !path.parent()?.join("mesh.glb").exists() {
+        return None;
+    }
+
+    Some(path.parent()?.file_name()?.to_str()?.to_string())
+}
+
+pub fn setup_model_watcher(mut commands: Commands, generator: Res<Generator>) {
+    match ModelWatcher::new(generator.config().writes_in_place()) {
+        Ok(watcher) => commands.insert_resource(watcher),
+        Err(err) => println!("Failed to watch {}: {}", MODELS_DIR, err),
+    }
+}
+
+/// Finishes the in-flight job of every noun whose model just arrived.
+pub fn forward_model_arrivals(
+    time: Res<Time>,
+    watcher: Option<Res<ModelWatcher>>,
+    mut queue: ResMut<GenerationQueue>,
+    mut updates: EventWriter<GenerationUpdate>,
+    mut last_poll: Local<f32>,
+) {
+    let Some(watcher) = watcher else {
+        return;
+    };
+
+    let mut arrived: Vec<String> = watcher.arrivals.lock().unwrap().try_iter().collect();
+
+    // for platforms that do not report files being closed
+    let now = time.elapsed_seconds();
+    if watcher.in_place && now - *last_poll >= POLL_INTERVAL {
+        *last_poll = now;
+        arrived.extend(queue.in_flight().filter(|noun| is_settled(noun)).cloned());
+    }
+
+    for noun in arrived {
+        // jobs that already reported success were finished by their response
+        if queue.is_in_flight(&noun) {
+            queue.finish(&noun);
+            updates.send(GenerationUpdate::Finished { noun });
+        }
+    }
+}
+
+/// Whether `<noun>/mesh.glb` exists and has not been written to for a while.
+fn is_settled(noun: &str) -> bool {
+    std::fs::metadata(Path::new(MODELS_DIR).join(noun).join("mesh.glb"))
+        .and_then(|metadata| metadata.modified())
+        .is_ok_and(|modified| modified.elapsed().is_ok_and(|age| age >= SETTLE_TIME))
+}
@@ -0,0 +1,337 @@
+use std::collections::{HashMap, VecDeque};
+
+use bevy::prelude::*;
+use bevy_mod_picking::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::{
+    command::{CommandFeedback, CommandInvocation, CommandSet, CommandSpec, RegisterCommand},
+    ui::{ObjectId, ObjectOrigin, SpawnedObject, TypingState},
+};
+
+/// Oldest entries are forgotten past this many.
+const MAX_HISTORY: usize = 100;
+
+pub struct HistoryPlugin;
+
+impl Plugin for HistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<History>()
+            .add_event::<ObjectSpawned>()
+            .add_event::<RemoveObject>()
+            .add_event::<RestoreObject>()
+            .register_command(CommandSpec::new(
+                "undo",
+                "Reverts the last spawn, removal or move",
+            ))
+            .register_command(CommandSpec::new(
+                "redo",
+                "Applies what was last undone again",
+            ))
+            .add_systems(
+                Update,
+                (
+                    record_spawns,
+                    remove_objects.after(CommandSet::Handle),
+                    record_moves,
+                    run_history_commands.in_set(CommandSet::Handle),
+                    history_shortcuts.run_if(in_state(TypingState::IsMoving)),
+                ),
+            );
+    }
+}
+
+/// Sent by `spawn_listener` for every new object, but not for restored ones.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct ObjectSpawned(pub ObjectId);
+
+/// Despawns a spawned object so that it can be undone.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct RemoveObject(pub Entity);
+
+/// Spawns an object again from a snapshot, with its old id.
+#[derive(Event, Debug, Clone)]
+pub struct RestoreObject(pub ObjectSnapshot);
+
+/// Everything needed to bring a removed object back.
+#[derive(Debug, Clone)]
+pub struct ObjectSnapshot {
+    pub id: ObjectId,
+    pub phrase: String,
+    pub transform: Transform,
+    pub velocity: Velocity,
+    /// Held in place by `/freeze`.
+    pub frozen: bool,
+    /// The object this one is joined to by `/glue`.
+    pub glued_to: Option<ObjectId>,
+}
+
+/// A change to the world, along with enough to revert it.
+#[derive(Debug, Clone)]
+enum HistoryEntry {
+    Spawned(Vec<ObjectId>),
+    Removed(Vec<ObjectSnapshot>),
+    Moved {
+        id: ObjectId,
+        from: Transform,
+        to: Transform,
+    },
+}
+
+#[derive(Resource, Default)]
+pub struct History {
+    undo: VecDeque<HistoryEntry>,
+    redo: Vec<HistoryEntry>,
+}
+
+impl History {
+    fn record(&mut self, entry: HistoryEntry) {
+        self.undo.push_back(entry);
+        if self.undo.len() > MAX_HISTORY {
+            self.undo.pop_front();
+        }
+
+        // a new change makes the undone ones unreachable
+        self.redo.clear();
+    }
+
+    pub fn clear(&mut self) {
+        self.undo.clear();
+        self.redo.clear();
+    }
+}
+
+type ObjectQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static ObjectId,
+        &'static ObjectOrigin,
+        &'static mut Transform,
+        Option<&'static mut Velocity>,
+        Option<&'static RigidBody>,
+        Option<&'static ImpulseJoint>,
+    ),
+    With<SpawnedObject>,
+>;
+
+fn snapshot(object_query: &ObjectQuery, entity: Entity) -> Option<ObjectSnapshot> {
+    let (_, id, origin, transform, velocity, body, joint) = object_query.get(entity).ok()?;
+    let glued_to = joint
+        .and_then(|joint| object_query.get(joint.parent).ok())
+        .map(|(_, parent_id, ..)| *parent_id);
+
+    Some(ObjectSnapshot {
+        id: *id,
+        phrase: origin.phrase.clone(),
+        transform: *transform,
+        velocity: velocity.copied().unwrap_or_default(),
+        frozen: body == Some(&RigidBody::Fixed),
+        glued_to,
+    })
+}
+
+/// Applies the opposite of `entry` and returns what reverts that in turn.
+fn revert(
+    entry: HistoryEntry,
+    commands: &mut Commands,
+    restores: &mut EventWriter<RestoreObject>,
+    object_query: &mut ObjectQuery,
+) -> Option<HistoryEntry> {
+    match entry {
+        HistoryEntry::Spawned(ids) => {
+            let snapshots: Vec<ObjectSnapshot> = object_query
+                .iter()
+                .filter(|(_, id, ..)| ids.contains(*id))
+                .filter_map(|(entity, ..)| snapshot(object_query, entity))
+                .collect();
+
+            for (entity, id, ..) in object_query.iter() {
+                if ids.contains(id) {
+                    commands.entity(entity).despawn_recursive();
+                }
+            }
+
+            (!snapshots.is_empty()).then_some(HistoryEntry::Removed(snapshots))
+        }
+        HistoryEntry::Removed(snapshots) => {
+            let ids = snapshots.iter().map(|snapshot| snapshot.id).collect();
+            restores.send_batch(snapshots.into_iter().map(RestoreObject));
+
+            Some(HistoryEntry::Spawned(ids))
+        }
+        HistoryEntry::Moved { id, from, to } => {
+            let (_, _, _, mut transform, velocity, ..) = object_query
+                .iter_mut()
+                .find(|(_, object_id, ..)| **object_id == id)?;
+
+            *transform = from;
+            if let Some(mut velocity) = velocity {
+                *velocity = Velocity::zero();
+            }
+
+            Some(HistoryEntry::Moved {
+                id,
+                from: to,
+                to: from,
+            })
+        }
+    }
+}
+
+fn record_spawns(mut history: ResMut<History>, mut events: EventReader<ObjectSpawned>) {
+    for ObjectSpawned(id) in events.read() {
+        history.record(HistoryEntry::Spawned(vec![*id]));
+    }
+}
+
+fn remove_objects(
+    mut commands: Commands,
+    mut history: ResMut<History>,
+    mut events: EventReader<RemoveObject>,
+    object_query: ObjectQuery,
+) {
+    // everything removed in the same frame, e.g. by `/clear`, is undone at once
+    let mut snapshots = vec![];
+
+    for RemoveObject(entity) in events.read() {
+        if let Some(object) = snapshot(&object_query, *entity) {
+            snapshots.push(object);
+        }
+
+        if let Some(entity) = commands.get_entity(*entity) {
+            entity.despawn_recursive();
+        }
+    }
+
+    if !snapshots.is_empty() {
+        history.record(HistoryEntry::Removed(snapshots));
+    }
+}
+
+fn record_moves(
+    mut history: ResMut<History>,
+    mut drag_starts: EventReader<Pointer<DragStart>>,
+    mut drag_ends: EventReader<Pointer<DragEnd>>,
+    mut dragged: Local<HashMap<Entity, Transform>>,
+    object_query: Query<(&ObjectId, &Transform), With<SpawnedObject>>,
+) {
+    for event in drag_starts.read() {
+        if let Ok((_, transform)) = object_query.get(event.target) {
+            dragged.insert(event.target, *transform);
+        }
+    }
+
+    for event in drag_ends.read() {
+        let Some(from) = dragged.remove(&event.target) else {
+            continue;
+        };
+        let Ok((id, to)) = object_query.get(event.target) else {
+            continue;
+        };
+
+        if from.translation != to.translation || from.rotation != to.rotation {
+            history.record(HistoryEntry::Moved {
+                id: *id,
+                from,
+                to: *to,
+            });
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum HistoryStep {
+    Undo,
+    Redo,
+}
+
+fn step(
+    direction: HistoryStep,
+    history: &mut History,
+    commands: &mut Commands,
+    restores: &mut EventWriter<RestoreObject>,
+    feedback: &mut CommandFeedback,
+    object_query: &mut ObjectQuery,
+) {
+    let entry = match direction {
+        HistoryStep::Undo => history.undo.pop_back(),
+        HistoryStep::Redo => history.redo.pop(),
+    };
+
+    let Some(entry) = entry else {
+        feedback.send(format!("Nothing to {:?}", direction));
+        return;
+    };
+
+    // objects that are gone for good leave nothing to revert
+    let Some(reverted) = revert(entry, commands, restores, object_query) else {
+        return;
+    };
+
+    match direction {
+        HistoryStep::Undo => history.redo.push(reverted),
+        HistoryStep::Redo => history.undo.push_back(reverted),
+    }
+}
+
+fn run_history_commands(
+    mut commands: Commands,
+    mut history: ResMut<History>,
+    mut invocations: EventReader<CommandInvocation>,
+    mut restores: EventWriter<RestoreObject>,
+    mut feedback: CommandFeedback,
+    mut object_query: ObjectQuery,
+) {
+    for invocation in invocations.read() {
+        let direction = match invocation.name.as_str() {
+            "undo" => HistoryStep::Undo,
+            "redo" => HistoryStep::Redo,
+            _ => continue,
+        };
+
+        step(
+            direction,
+            &mut history,
+            &mut commands,
+            &mut restores,
+            &mut feedback,
+            &mut object_query,
+        );
+    }
+}
+
+/// Ctrl+Z undoes, Ctrl+Y or Ctrl+Shift+Z redoes.
+fn history_shortcuts(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut commands: Commands,
+    mut history: ResMut<History>,
+    mut restores: EventWriter<RestoreObject>,
+    mut feedback: CommandFeedback,
+    mut object_query: ObjectQuery,
+) {
+    if !keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]) {
+        return;
+    }
+
+    let shift = keyboard_input.any_pressed([KeyCode::ShiftLeft, KeyCode::ShiftRight]);
+    let direction = if keyboard_input.just_pressed(KeyCode::KeyY)
+        || (shift && keyboard_input.just_pressed(KeyCode::KeyZ))
+    {
+        HistoryStep::Redo
+    } else if keyboard_input.just_pressed(KeyCode::KeyZ) {
+        HistoryStep::Undo
+    } else {
+        return;
+    };
+
+    step(
+        direction,
+        &mut history,
+        &mut commands,
+        &mut restores,
+        &mut feedback,
+        &mut object_query,
+    );
+}
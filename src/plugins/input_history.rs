@@ -0,0 +1,194 @@
+use std::{fs::OpenOptions, io, path::PathBuf};
+
+use bevy::prelude::*;
+use bevy_simple_text_input::{TextInputCursorPos, TextInputSubmitEvent, TextInputValue};
+
+use super::{save::SAVES_DIR, ui::TypingState};
+
+const HISTORY_FILE: &str = "input_history.ron";
+/// Older submissions are forgotten past this many.
+const MAX_ENTRIES: usize = 500;
+
+pub struct InputHistoryPlugin;
+
+impl Plugin for InputHistoryPlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(InputHistory::import())
+            .add_systems(OnEnter(TypingState::IsTyping), reset_input_history)
+            .add_systems(Update, record_submissions)
+            // the text input handles keys in `Update`, so its edits are in by now
+            .add_systems(
+                PostUpdate,
+                recall_input_history.run_if(in_state(TypingState::IsTyping)),
+            );
+    }
+}
+
+/// Everything submitted in the typing box, oldest first, kept across sessions.
+#[derive(Resource, Default)]
+pub struct InputHistory {
+    entries: Vec<String>,
+    /// The entry shown by Up/Down, if any.
+    cursor: Option<usize>,
+    /// What was typed before Up was first pressed, brought back by Down.
+    draft: String,
+    /// The text searched for with Ctrl+R and the entry it last matched.
+    search: Option<(String, usize)>,
+}
+
+impl InputHistory {
+    fn path() -> PathBuf {
+        PathBuf::from(SAVES_DIR).join(HISTORY_FILE)
+    }
+
+    pub fn import() -> Self {
+        let Ok(file) = OpenOptions::new().read(true).open(Self::path()) else {
+            return InputHistory::default();
+        };
+
+        match ron::de::from_reader(file) {
+            Ok(entries) => InputHistory {
+                entries,
+                ..default()
+            },
+            Err(err) => {
+                println!("Failed to read input history: {}", err);
+                InputHistory::default()
+            }
+        }
+    }
+
+    pub fn export(&self) {
+        let result = ron::ser::to_string_pretty(&self.entries, Default::default())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+            .and_then(|content| {
+                std::fs::create_dir_all(SAVES_DIR)?;
+                std::fs::write(Self::path(), content)
+            });
+
+        if let Err(err) = result {
+            println!("Failed to store input history: {}", err);
+        }
+    }
+
+    pub fn push(&mut self, line: &str) {
+        let line = line.trim();
+        if line.is_empty() || self.entries.last().is_some_and(|last| last == line) {
+            return;
+        }
+
+        self.entries.push(line.to_string());
+        if self.entries.len() > MAX_ENTRIES {
+            self.entries.remove(0);
+        }
+    }
+
+    fn older(&mut self, current: &str) -> Option<&str> {
+        let index = match self.cursor {
+            Some(index) => index.checked_sub(1)?,
+            None => {
+                self.draft = current.to_string();
+                self.entries.len().checked_sub(1)?
+            }
+        };
+
+        self.cursor = Some(index);
+        Some(&self.entries[index])
+    }
+
+    fn newer(&mut self) -> Option<&str> {
+        let index = self.cursor? + 1;
+
+        if index < self.entries.len() {
+            self.cursor = Some(index);
+            Some(&self.entries[index])
+        } else {
+            self.cursor = None;
+            Some(&self.draft)
+        }
+    }
+
+    /// The newest entry containing the searched text, older than the last match
+    /// while the input still shows that match.
+    fn search(&mut self, current: &str) -> Option<&str> {
+        let (query, before) = match self.search.take() {
+            Some((query, index)) if self.entries.get(index).is_some_and(|e| e == current) => {
+                (query, index)
+            }
+            _ => (current.to_string(), self.entries.len()),
+        };
+
+        let found = self.entries[..before]
+            .iter()
+            .rposition(|entry| entry.contains(&query));
+
+        match found {
+            Some(index) => {
+                self.search = Some((query, index));
+                Some(&self.entries[index])
+            }
+            None => None,
+        }
+    }
+}
+
+fn reset_input_history(mut history: ResMut<InputHistory>) {
+    history.cursor = None;
+    history.search = None;
+}
+
+fn record_submissions(
+    mut history: ResMut<InputHistory>,
+    mut events: EventReader<TextInputSubmitEvent>,
+) {
+    let mut changed = false;
+    for TextInputSubmitEvent { value, .. } in events.read() {
+        history.push(value);
+        changed = true;
+    }
+
+    if changed {
+        history.export();
+    }
+}
+
+/// Up and Down step through earlier submissions, Ctrl+R finds one containing
+/// what is typed and, pressed again, an older one.
+fn recall_input_history(
+    keyboard_input: Res<ButtonInput<KeyCode>>,
+    mut history: ResMut<InputHistory>,
+    mut input_query: Query<(&mut TextInputValue, &mut TextInputCursorPos)>,
+) {
+    let Ok((mut value, mut cursor)) = input_query.get_single_mut() else {
+        return;
+    };
+
+    let ctrl = keyboard_input.any_pressed([KeyCode::ControlLeft, KeyCode::ControlRight]);
+    let recalled = if keyboard_input.just_pressed(KeyCode::ArrowUp) {
+        history.older(&value.0)
+    } else if keyboard_input.just_pressed(KeyCode::ArrowDown) {
+        history.newer()
+    } else if ctrl && keyboard_input.just_pressed(KeyCode::KeyR) {
+        // the text input typed the `r` of Ctrl+R before the cursor, Ctrl or not
+        if cursor.0 > 0 {
+            cursor.0 -= 1;
+            value.0 = remove_char_at(&value.0, cursor.0);
+        }
+        history.search(&value.0)
+    } else {
+        return;
+    };
+
+    if let Some(recalled) = recalled {
+        value.0 = recalled.to_string();
+        cursor.0 = value.0.chars().count();
+    }
+}
+
+fn remove_char_at(text: &str, index: usize) -> String {
+    text.chars()
+        .enumerate()
+        .filter(|(i, _)| *i != index)
+        .map(|(_, c)| c)
+        .collect()
+}
@@ -0,0 +1,441 @@
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs::OpenOptions,
+    io,
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::components::noun::{is_noun_slug, NounRegistry};
+
+use super::{
+    collider::PendingCollider,
+    command::{
+        ArgKind, CommandFeedback, CommandInvocation, CommandSet, CommandSpec, RegisterCommand,
+    },
+    generator::{
+        protocol::GenerationRequest,
+        queue::{GenerationQueue, GenerationUpdate},
+        Generator, GeneratorConfig,
+    },
+    normalize::PendingNormalization,
+    notification::Notification,
+    ui::{SpawnedObject, PLACEHOLDER_NOUN},
+};
+
+const MODELS_DIR: &str = "assets/models";
+const METADATA_FILE: &str = "metadata.ron";
+/// Holds the generated files of a model while it is regenerated, until the new ones arrive.
+const PREVIOUS_DIR: &str = "previous";
+/// Everything a generator run produces or that is computed from its output.
+/// `noun.ron` and the metadata are kept when a model is regenerated.
+const GENERATED_FILES: [&str; 6] = [
+    "mesh.glb",
+    "ready",
+    "raw_input.png",
+    "processed_input.png",
+    "collider.ron",
+    "normalization.ron",
+];
+
+pub struct ModelLibraryPlugin;
+
+impl Plugin for ModelLibraryPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<ModelReplaced>()
+            .insert_resource(ModelLibrary::scan())
+            .register_command(
+                CommandSpec::new("library", "Lists generated models, or manages one")
+                    .optional_arg(
+                        "action",
+                        ArgKind::Choice(&["list", "tag", "delete", "regenerate"]),
+                    )
+                    .optional_arg("name", ArgKind::Word),
+            )
+            .add_systems(
+                Update,
+                (
+                    record_generated_models,
+                    respawn_replaced_models,
+                    run_library_commands.in_set(CommandSet::Handle),
+                ),
+            );
+    }
+}
+
+/// Sent when a regenerated model arrived, and again once it is loaded, so data computed
+/// from the old one is dropped.
+#[derive(Event, Debug, Clone)]
+pub struct ModelReplaced {
+    pub noun: String,
+}
+
+/// How a model was made, stored in `assets/models/<noun>/metadata.ron`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct ModelMetadata {
+    pub prompt: String,
+    /// The backend and its settings; `None` for models added by hand.
+    pub generator: Option<GeneratorConfig>,
+    /// Seconds since the Unix epoch.
+    pub created: Option<u64>,
+    pub tags: Vec<String>,
+}
+
+impl ModelMetadata {
+    fn path(noun: &str) -> PathBuf {
+        Path::new(MODELS_DIR).join(noun).join(METADATA_FILE)
+    }
+
+    pub fn import(noun: &str) -> Option<Self> {
+        let file = OpenOptions::new().read(true).open(Self::path(noun)).ok()?;
+
+        match ron::de::from_reader(file) {
+            Ok(metadata) => Some(metadata),
+            Err(err) => {
+                println!("Failed to read metadata for {}: {}", noun, err);
+                None
+            }
+        }
+    }
+
+    pub fn export(&self, noun: &str) {
+        let result = ron::ser::to_string_pretty(self, Default::default())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+            .and_then(|content| std::fs::write(Self::path(noun), content));
+
+        if let Err(err) = result {
+            println!("Failed to store metadata for {}: {}", noun, err);
+        }
+    }
+
+    /// Stand-in for models without a metadata file, tagged from their `noun.ron`.
+    fn unrecorded(noun: &str) -> Self {
+        ModelMetadata {
+            prompt: GenerationRequest::new(0, noun).prompt,
+            tags: NounRegistry::import(noun)
+                .map(|entry| entry.tags)
+                .unwrap_or_default(),
+            ..default()
+        }
+    }
+}
+
+/// Index of every model in `assets/models`, by noun.
+#[derive(Resource, Default)]
+pub struct ModelLibrary {
+    models: BTreeMap<String, ModelMetadata>,
+    regenerating: HashSet<String>,
+    /// Regenerated models whose scene has not been reloaded yet.
+    reloading: HashSet<String>,
+}
+
+impl ModelLibrary {
+    pub fn scan() -> Self {
+        let mut library = ModelLibrary::default();
+
+        let Ok(entries) = std::fs::read_dir(MODELS_DIR) else {
+            return library;
+        };
+
+        for entry in entries.filter_map(|entry| entry.ok()) {
+            if !entry.path().join("mesh.glb").exists() {
+                continue;
+            }
+
+            let Ok(noun) = entry.file_name().into_string() else {
+                continue;
+            };
+
+            let metadata =
+                ModelMetadata::import(&noun).unwrap_or_else(|| ModelMetadata::unrecorded(&noun));
+            library.models.insert(noun, metadata);
+        }
+
+        library
+    }
+
+    pub fn list(&self) -> impl Iterator<Item = (&String, &ModelMetadata)> {
+        self.models.iter()
+    }
+
+    pub fn get(&self, noun: &str) -> Option<&ModelMetadata> {
+        self.models.get(noun)
+    }
+
+    pub fn search_tag<'a>(&'a self, tag: &'a str) -> impl Iterator<Item = &'a String> {
+        self.models
+            .iter()
+            .filter(move |(_, metadata)| metadata.tags.iter().any(|t| t == tag))
+            .map(|(noun, _)| noun)
+    }
+
+    /// Writes the metadata of a model that just arrived and adds it to the index.
+    pub fn record(&mut self, noun: &str, metadata: ModelMetadata) {
+        metadata.export(noun);
+        self.models.insert(noun.to_string(), metadata);
+    }
+
+    /// Removes the model and everything stored next to it.
+    pub fn delete(&mut self, noun: &str) -> io::Result<()> {
+        let dir = replaceable_model_dir(noun)?;
+        self.models.remove(noun);
+        self.regenerating.remove(noun);
+        std::fs::remove_dir_all(dir)
+    }
+
+    /// Sets the generated files of a model aside and queues it again, keeping its
+    /// `noun.ron` and tags. The old files come back if generation fails.
+    pub fn regenerate(
+        &mut self,
+        noun: &str,
+        queue: &mut GenerationQueue,
+        generator: &Generator,
+    ) -> io::Result<()> {
+        let dir = replaceable_model_dir(noun)?;
+
+        if generator.is_offline() {
+            return Err(io::Error::new(
+                io::ErrorKind::Unsupported,
+                "mesh generation is offline",
+            ));
+        }
+        if queue.contains(noun) {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "it is already being generated",
+            ));
+        }
+
+        let previous = dir.join(PREVIOUS_DIR);
+        std::fs::create_dir_all(&previous)?;
+        for file in GENERATED_FILES {
+            let path = dir.join(file);
+            if path.exists() {
+                std::fs::rename(path, previous.join(file))?;
+            }
+        }
+
+        self.models.remove(noun);
+        self.regenerating.insert(noun.to_string());
+        queue.enqueue(noun);
+
+        Ok(())
+    }
+
+    /// Puts the files set aside by [`Self::regenerate`] back in place.
+    fn restore_previous(&mut self, noun: &str) -> io::Result<()> {
+        let dir = model_dir(noun)?;
+        let previous = dir.join(PREVIOUS_DIR);
+
+        for file in GENERATED_FILES {
+            let path = previous.join(file);
+            if path.exists() {
+                std::fs::rename(path, dir.join(file))?;
+            }
+        }
+        std::fs::remove_dir_all(previous)?;
+
+        let metadata =
+            ModelMetadata::import(noun).unwrap_or_else(|| ModelMetadata::unrecorded(noun));
+        self.models.insert(noun.to_string(), metadata);
+
+        Ok(())
+    }
+}
+
+/// The directory of an existing model. Refuses anything but a slug, so `..` or
+/// `/` cannot reach outside `assets/models`.
+fn model_dir(noun: &str) -> io::Result<PathBuf> {
+    if !is_noun_slug(noun) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{:?} is not a valid noun", noun),
+        ));
+    }
+
+    let dir = Path::new(MODELS_DIR).join(noun);
+    if !dir.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("there is no model named {}", noun),
+        ));
+    }
+
+    Ok(dir)
+}
+
+/// Like [`model_dir`], but also refuses the placeholder every loading object uses.
+fn replaceable_model_dir(noun: &str) -> io::Result<PathBuf> {
+    if noun == PLACEHOLDER_NOUN {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} is the placeholder of every loading object", noun),
+        ));
+    }
+
+    model_dir(noun)
+}
+
+fn record_generated_models(
+    mut events: EventReader<GenerationUpdate>,
+    mut library: ResMut<ModelLibrary>,
+    mut replaced: EventWriter<ModelReplaced>,
+    mut notifications: EventWriter<Notification>,
+    asset_server: Res<AssetServer>,
+    generator: Res<Generator>,
+) {
+    for event in events.read() {
+        let noun = match event {
+            GenerationUpdate::Finished { noun } => noun,
+            GenerationUpdate::Failed { noun, reason } if library.regenerating.remove(noun) => {
+                let message = match library.restore_previous(noun) {
+                    Ok(()) => format!(
+                        "Regenerating {} failed ({}), kept the old model",
+                        noun, reason
+                    ),
+                    Err(err) => format!(
+                        "Regenerating {} failed ({}) and the old model could not be restored: {}",
+                        noun, reason, err
+                    ),
+                };
+                notifications.send(Notification(message));
+                continue;
+            }
+            _ => continue,
+        };
+
+        // tags survive regeneration
+        let tags = ModelMetadata::import(noun)
+            .unwrap_or_else(|| ModelMetadata::unrecorded(noun))
+            .tags;
+        let created = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|elapsed| elapsed.as_secs())
+            .ok();
+
+        library.record(
+            noun,
+            ModelMetadata {
+                prompt: GenerationRequest::new(0, noun).prompt,
+                generator: Some(generator.config().clone()),
+                created,
+                tags,
+            },
+        );
+
+        if library.regenerating.remove(noun) {
+            if let Ok(dir) = model_dir(noun) {
+                let _ = std::fs::remove_dir_all(dir.join(PREVIOUS_DIR));
+            }
+
+            asset_server.reload(format!("models/{noun}/mesh.glb"));
+            library.reloading.insert(noun.clone());
+            replaced.send(ModelReplaced { noun: noun.clone() });
+        }
+    }
+}
+
+/// Spawns the scene of every object using a regenerated model again once the new model
+/// is loaded, so that it is normalized and gets a collider like a new object.
+fn respawn_replaced_models(
+    mut commands: Commands,
+    mut events: EventReader<AssetEvent<Scene>>,
+    mut library: ResMut<ModelLibrary>,
+    mut noun_registry: ResMut<NounRegistry>,
+    mut replaced: EventWriter<ModelReplaced>,
+    asset_server: Res<AssetServer>,
+    mut scene_query: Query<(Entity, &mut Handle<Scene>), With<SpawnedObject>>,
+) {
+    for event in events.read() {
+        let AssetEvent::Modified { id } = event else {
+            continue;
+        };
+
+        // scenes are loaded from `models/<noun>/mesh.glb`
+        let Some(noun) = asset_server.get_path(*id).and_then(|path| {
+            let noun = path.path().parent()?.file_name()?.to_str()?;
+            Some(noun.to_string())
+        }) else {
+            continue;
+        };
+        if !library.reloading.remove(&noun) {
+            continue;
+        }
+
+        // anything measured while the old model was still loaded is stale
+        replaced.send(ModelReplaced { noun: noun.clone() });
+
+        let entry = noun_registry.get(&noun);
+        for (entity, mut scene) in scene_query.iter_mut() {
+            if scene.id() != *id {
+                continue;
+            }
+
+            // a changed handle makes the scene spawner replace the instance
+            scene.set_changed();
+            commands
+                .entity(entity)
+                .insert(PendingNormalization { noun: noun.clone() });
+            if let Some(kind) = entry.collider.mesh_collider() {
+                commands.entity(entity).insert(PendingCollider {
+                    noun: noun.clone(),
+                    kind,
+                });
+            }
+        }
+    }
+}
+
+fn run_library_commands(
+    mut library: ResMut<ModelLibrary>,
+    mut queue: ResMut<GenerationQueue>,
+    generator: Res<Generator>,
+    mut noun_registry: ResMut<NounRegistry>,
+    mut invocations: EventReader<CommandInvocation>,
+    mut feedback: CommandFeedback,
+) {
+    for invocation in invocations.read() {
+        if invocation.name != "library" {
+            continue;
+        }
+
+        match (invocation.word(0).unwrap_or("list"), invocation.word(1)) {
+            ("list", _) => {
+                let models: Vec<String> = library
+                    .list()
+                    .map(|(noun, metadata)| format!("{} [{}]", noun, metadata.tags.join(", ")))
+                    .collect();
+                if models.is_empty() {
+                    feedback.send("The library is empty");
+                } else {
+                    feedback.send(models.join("\n"));
+                }
+            }
+            ("tag", Some(tag)) => {
+                let nouns: Vec<&str> = library.search_tag(tag).map(String::as_str).collect();
+                if nouns.is_empty() {
+                    feedback.send(format!("No models tagged {}", tag));
+                } else {
+                    feedback.send(nouns.join("\n"));
+                }
+            }
+            ("delete", Some(noun)) => {
+                if let Err(err) = library.delete(noun) {
+                    feedback.send(format!("Failed to delete {}: {}", noun, err));
+                }
+                noun_registry.entries.remove(noun);
+            }
+            ("regenerate", Some(noun)) => {
+                if let Err(err) = library.regenerate(noun, &mut queue, &generator) {
+                    feedback.send(format!("Failed to regenerate {}: {}", noun, err));
+                }
+            }
+            (action, None) => feedback.send(format!("/library {} needs a name", action)),
+            _ => {}
+        }
+    }
+}
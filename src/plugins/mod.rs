@@ -1,10 +1,34 @@
 pub mod assets;
+pub mod autosave;
+pub mod browser;
+pub mod collider;
+pub mod command;
+pub mod fallback;
+pub mod generator;
+pub mod history;
+pub mod input_history;
+pub mod library;
+pub mod normalize;
+pub mod notification;
+pub mod physics;
+pub mod placement;
 pub mod player;
+pub mod progress;
+pub mod save;
+pub mod script;
+pub mod structure;
 pub mod ui;
 pub mod world;
 
 pub mod prelude {
     pub use super::{
-        assets::CustomAssetPlugin, player::PlayerPlugin, ui::MainUiPlugin, world::WorldPlugin,
+        assets::CustomAssetPlugin, autosave::AutosavePlugin, browser::LibraryBrowserPlugin,
+        collider::MeshColliderPlugin, command::CommandPlugin, fallback::GenerationFallbackPlugin,
+        generator::GeneratorPlugin, history::HistoryPlugin, input_history::InputHistoryPlugin,
+        library::ModelLibraryPlugin, normalize::MeshNormalizationPlugin,
+        notification::NotificationPlugin, physics::PhysicsControlPlugin,
+        placement::PlacementPlugin, player::PlayerPlugin, progress::GenerationProgressPlugin,
+        save::SavePlugin, script::ScriptPlugin, structure::StructurePlugin, ui::MainUiPlugin,
+        world::WorldPlugin,
     };
 }
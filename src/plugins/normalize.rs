@@ -0,0 +1,166 @@
+use std::{
+    collections::HashMap,
+    fs::OpenOptions,
+    io,
+    path::{Path, PathBuf},
+};
+
+use bevy::{prelude::*, scene::SceneInstance};
+use serde::{Deserialize, Serialize};
+
+use crate::components::noun::NounRegistry;
+
+use super::{
+    collider::{attach_collider_to_scene, collect_scene_geometry},
+    library::ModelReplaced,
+};
+
+/// Largest extent of a model after normalization, before the noun's own scale.
+const NORMALIZED_SIZE: f32 = 1.0;
+
+pub struct MeshNormalizationPlugin;
+
+impl Plugin for MeshNormalizationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            normalize_scene.before(attach_collider_to_scene).run_if(
+                any_with_component::<PendingNormalization>.or_else(on_event::<ModelReplaced>()),
+            ),
+        );
+    }
+}
+
+/// Marks a scene whose children still have to be moved into the normalized frame.
+#[derive(Component)]
+pub struct PendingNormalization {
+    pub noun: String,
+}
+
+/// Transform that makes a model upright, unit sized and pivoted at its bottom center.
+/// Stored in `assets/models/<noun>/normalization.ron`; delete it to measure the mesh again.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq)]
+pub struct Normalization {
+    pub translation: (f32, f32, f32),
+    pub rotation: (f32, f32, f32, f32),
+    pub scale: f32,
+}
+
+impl Normalization {
+    fn path(noun: &str) -> PathBuf {
+        Path::new("assets/models")
+            .join(noun)
+            .join("normalization.ron")
+    }
+
+    pub fn import(noun: &str) -> Option<Self> {
+        let file = OpenOptions::new().read(true).open(Self::path(noun)).ok()?;
+
+        ron::de::from_reader(file).ok()
+    }
+
+    pub fn export(&self, noun: &str) {
+        let result = ron::ser::to_string_pretty(self, Default::default())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+            .and_then(|content| std::fs::write(Self::path(noun), content));
+
+        if let Err(err) = result {
+            println!("Failed to store normalization for {}: {}", noun, err);
+        }
+    }
+
+    /// Measures `vertices` once rotated by `orientation` and fits them into the unit frame.
+    pub fn compute(orientation: Quat, vertices: &[Vec3]) -> Self {
+        let rotated: Vec<Vec3> = vertices.iter().map(|&v| orientation * v).collect();
+
+        let min = rotated.iter().copied().fold(Vec3::MAX, Vec3::min);
+        let max = rotated.iter().copied().fold(Vec3::MIN, Vec3::max);
+
+        let size = (max - min).max_element().max(f32::EPSILON);
+        let scale = NORMALIZED_SIZE / size;
+        let pivot = Vec3::new((min.x + max.x) / 2.0, min.y, (min.z + max.z) / 2.0);
+        let translation = -pivot * scale;
+
+        Normalization {
+            translation: (translation.x, translation.y, translation.z),
+            rotation: (orientation.x, orientation.y, orientation.z, orientation.w),
+            scale,
+        }
+    }
+
+    pub fn transform(&self) -> Transform {
+        let (x, y, z) = self.translation;
+        let (qx, qy, qz, qw) = self.rotation;
+
+        Transform {
+            translation: Vec3::new(x, y, z),
+            rotation: Quat::from_xyzw(qx, qy, qz, qw).normalize(),
+            scale: Vec3::splat(self.scale),
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn normalize_scene(
+    mut commands: Commands,
+    mut computed: Local<HashMap<String, Normalization>>,
+    mut replaced: EventReader<ModelReplaced>,
+    mut noun_registry: ResMut<NounRegistry>,
+    meshes: Res<Assets<Mesh>>,
+    scene_spawner: Res<SceneSpawner>,
+    pending_query: Query<(Entity, &PendingNormalization, &SceneInstance)>,
+    children_query: Query<&Children>,
+    mesh_query: Query<&Handle<Mesh>>,
+    transform_query: Query<(&Transform, Option<&Parent>)>,
+) {
+    for ModelReplaced { noun } in replaced.read() {
+        computed.remove(noun);
+    }
+
+    for (entity, pending, instance) in pending_query.iter() {
+        if !scene_spawner.instance_is_ready(**instance) {
+            continue;
+        }
+
+        let noun = &pending.noun;
+
+        if !computed.contains_key(noun) {
+            let normalization = match Normalization::import(noun) {
+                Some(normalization) => normalization,
+                None => {
+                    let Some((vertices, _)) = collect_scene_geometry(
+                        entity,
+                        &meshes,
+                        &children_query,
+                        &mesh_query,
+                        &transform_query,
+                    ) else {
+                        continue;
+                    };
+
+                    let orientation = noun_registry.get(noun).orientation();
+                    let normalization = Normalization::compute(orientation, &vertices);
+                    normalization.export(noun);
+                    normalization
+                }
+            };
+
+            computed.insert(noun.clone(), normalization);
+        }
+
+        let normalization = computed[noun].transform();
+
+        // the scene roots are spawned as direct children of the entity holding the scene
+        if let Ok(children) = children_query.get(entity) {
+            for &child in children.iter() {
+                if let Ok((transform, _)) = transform_query.get(child) {
+                    commands
+                        .entity(child)
+                        .insert(normalization.mul_transform(*transform));
+                }
+            }
+        }
+
+        commands.entity(entity).remove::<PendingNormalization>();
+    }
+}
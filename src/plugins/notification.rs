@@ -0,0 +1,97 @@
+use bevy::prelude::*;
+
+const NOTIFICATION_FONT_SIZE: f32 = 20.0;
+const NOTIFICATION_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
+const NOTIFICATION_BACKGROUND_COLOR: Color = Color::rgba(0.15, 0.15, 0.15, 0.8);
+const NOTIFICATION_DURATION: f32 = 4.0;
+
+pub struct NotificationPlugin;
+
+impl Plugin for NotificationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<Notification>()
+            .add_systems(Startup, setup_notification_area)
+            .add_systems(Update, (show_notifications, expire_notifications).chain());
+    }
+}
+
+/// A message shown to the player for a few seconds.
+#[derive(Event, Debug, Clone)]
+pub struct Notification(pub String);
+
+impl Notification {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self(message.into())
+    }
+}
+
+#[derive(Component)]
+struct NotificationArea;
+
+#[derive(Component, Deref, DerefMut)]
+struct NotificationTimer(Timer);
+
+fn setup_notification_area(mut commands: Commands) {
+    commands.spawn((
+        NotificationArea,
+        NodeBundle {
+            style: Style {
+                position_type: PositionType::Absolute,
+                top: Val::Px(10.0),
+                width: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                row_gap: Val::Px(4.0),
+                ..default()
+            },
+            ..default()
+        },
+    ));
+}
+
+fn show_notifications(
+    mut commands: Commands,
+    mut events: EventReader<Notification>,
+    area_query: Query<Entity, With<NotificationArea>>,
+) {
+    let Ok(area) = area_query.get_single() else {
+        return;
+    };
+
+    for Notification(message) in events.read() {
+        println!("{}", message);
+
+        let notification = commands
+            .spawn((
+                NotificationTimer(Timer::from_seconds(NOTIFICATION_DURATION, TimerMode::Once)),
+                TextBundle::from_section(
+                    message.clone(),
+                    TextStyle {
+                        font_size: NOTIFICATION_FONT_SIZE,
+                        color: NOTIFICATION_COLOR,
+                        ..default()
+                    },
+                )
+                .with_style(Style {
+                    padding: UiRect::all(Val::Px(4.0)),
+                    ..default()
+                })
+                .with_background_color(NOTIFICATION_BACKGROUND_COLOR),
+            ))
+            .id();
+
+        commands.entity(area).add_child(notification);
+    }
+}
+
+fn expire_notifications(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut NotificationTimer)>,
+) {
+    for (entity, mut timer) in query.iter_mut() {
+        if timer.tick(time.delta()).just_finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
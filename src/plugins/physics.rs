@@ -0,0 +1,141 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::command::{
+    ArgKind, CommandFeedback, CommandInvocation, CommandSet, CommandSpec, RegisterCommand,
+};
+
+const EARTH_GRAVITY: Vec3 = Vec3::new(0.0, -9.81, 0.0);
+const LOW_GRAVITY_FACTOR: f32 = 0.2;
+
+pub struct PhysicsControlPlugin;
+
+impl Plugin for PhysicsControlPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PhysicsStepping>()
+            .register_command(
+                CommandSpec::new(
+                    "gravity",
+                    "Sets gravity to x and y, or to normal, low, zero or reverse",
+                )
+                .arg("x", ArgKind::Word)
+                .optional_arg("y", ArgKind::Number),
+            )
+            .register_command(
+                CommandSpec::new("timescale", "Speeds the simulation up or slows it down")
+                    .arg("scale", ArgKind::Number),
+            )
+            .register_command(CommandSpec::new(
+                "pause",
+                "Pauses or resumes the simulation",
+            ))
+            .register_command(CommandSpec::new(
+                "step",
+                "Advances the paused simulation by one step",
+            ))
+            .add_systems(Update, run_physics_commands.in_set(CommandSet::Handle))
+            // the step has been simulated in `PostUpdate` by then
+            .add_systems(Last, end_physics_step);
+    }
+}
+
+/// Set by `/step` for the frame in which the paused pipeline runs once.
+#[derive(Resource, Default)]
+struct PhysicsStepping(bool);
+
+fn gravity_preset(name: &str, current: Vec3) -> Option<Vec3> {
+    match name {
+        "normal" => Some(EARTH_GRAVITY),
+        "low" => Some(EARTH_GRAVITY * LOW_GRAVITY_FACTOR),
+        "zero" => Some(Vec3::ZERO),
+        "reverse" => Some(-current),
+        _ => None,
+    }
+}
+
+fn run_physics_commands(
+    mut config: ResMut<RapierConfiguration>,
+    mut stepping: ResMut<PhysicsStepping>,
+    mut invocations: EventReader<CommandInvocation>,
+    mut feedback: CommandFeedback,
+) {
+    for invocation in invocations.read() {
+        match invocation.name.as_str() {
+            "gravity" => {
+                let Some(x) = invocation.word(0) else {
+                    continue;
+                };
+
+                let gravity = match (x.parse::<f32>(), invocation.number(1)) {
+                    (Ok(x), Some(y)) => Vec3::new(x, y, 0.0),
+                    (Ok(_), None) => {
+                        feedback.send(format!("/gravity {} needs a y as well", x));
+                        continue;
+                    }
+                    (Err(_), _) => match gravity_preset(x, config.gravity) {
+                        Some(gravity) => gravity,
+                        None => {
+                            feedback.send(format!("Unknown gravity {:?}", x));
+                            continue;
+                        }
+                    },
+                };
+
+                config.gravity = gravity;
+                feedback.send(format!("Gravity is now {}", gravity));
+            }
+            "timescale" => {
+                let Some(scale) = invocation.number(0) else {
+                    continue;
+                };
+                if scale <= 0.0 {
+                    feedback.send("Time scale should be above 0, use /pause to stop");
+                    continue;
+                }
+
+                match &mut config.timestep_mode {
+                    TimestepMode::Variable { time_scale, .. }
+                    | TimestepMode::Interpolated { time_scale, .. } => {
+                        *time_scale = scale;
+                        feedback.send(format!("Time scale is now {}", scale));
+                    }
+                    TimestepMode::Fixed { .. } => {
+                        feedback.send("The time scale cannot change with a fixed timestep")
+                    }
+                }
+            }
+            "pause" => {
+                config.physics_pipeline_active = !config.physics_pipeline_active;
+                stepping.0 = false;
+                feedback.send(format!(
+                    "Simulation {}",
+                    if config.physics_pipeline_active {
+                        "resumed"
+                    } else {
+                        "paused"
+                    }
+                ));
+            }
+            "step" => {
+                if config.physics_pipeline_active && !stepping.0 {
+                    feedback.send("/step only works while paused");
+                    continue;
+                }
+
+                config.physics_pipeline_active = true;
+                stepping.0 = true;
+            }
+            _ => {}
+        }
+    }
+}
+
+fn end_physics_step(
+    mut config: ResMut<RapierConfiguration>,
+    mut stepping: ResMut<PhysicsStepping>,
+) {
+    if stepping.0 {
+        config.physics_pipeline_active = false;
+        stepping.0 = false;
+    }
+}
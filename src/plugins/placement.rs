@@ -0,0 +1,133 @@
+use bevy::{ecs::system::SystemParam, prelude::*, window::PrimaryWindow};
+use bevy_rapier3d::prelude::*;
+
+use super::{
+    command::{ArgKind, CommandInvocation, CommandSet, CommandSpec, RegisterCommand},
+    player::Player,
+};
+
+const SPAWN_AHEAD: f32 = 4.0;
+const SPAWN_HEIGHT: f32 = 6.0;
+const OVERLAP_STEP: f32 = 1.0;
+const OVERLAP_MAX_ATTEMPTS: usize = 20;
+const FALLBACK_POSITION: Vec3 = Vec3::new(0.0, 20.0, 0.0);
+
+pub struct PlacementPlugin;
+
+impl Plugin for PlacementPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<SpawnPlacement>()
+            .register_command(
+                CommandSpec::new("spawnat", "Chooses where new objects appear")
+                    .arg("where", ArgKind::Choice(&["player", "cursor", "mouse"])),
+            )
+            .add_systems(Update, run_placement_commands.in_set(CommandSet::Handle));
+    }
+}
+
+fn run_placement_commands(
+    mut placement: ResMut<SpawnPlacement>,
+    mut invocations: EventReader<CommandInvocation>,
+) {
+    for invocation in invocations.read() {
+        if invocation.name != "spawnat" {
+            continue;
+        }
+
+        if let Some(mode) = invocation.word(0).and_then(SpawnPlacement::from_name) {
+            *placement = mode;
+        }
+    }
+}
+
+/// Where newly typed objects appear.
+#[derive(Resource, Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum SpawnPlacement {
+    /// In front of the player, above their head.
+    #[default]
+    Player,
+    /// Under the mouse cursor, projected onto the z=0 play plane.
+    Cursor,
+}
+
+impl SpawnPlacement {
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "player" => Some(SpawnPlacement::Player),
+            "cursor" | "mouse" => Some(SpawnPlacement::Cursor),
+            _ => None,
+        }
+    }
+}
+
+#[derive(SystemParam)]
+pub struct SpawnPlacer<'w, 's> {
+    placement: Res<'w, SpawnPlacement>,
+    rapier_context: Res<'w, RapierContext>,
+    camera_query: Query<'w, 's, (&'static Camera, &'static GlobalTransform), With<Camera3d>>,
+    window_query: Query<'w, 's, &'static Window, With<PrimaryWindow>>,
+    player_query: Query<'w, 's, &'static Transform, With<Player>>,
+}
+
+impl<'w, 's> SpawnPlacer<'w, 's> {
+    /// The spawn point for the current placement mode, before any overlap check.
+    /// Falls back to the player and then to the world center when the cursor
+    /// or the player are not available.
+    pub fn origin(&self) -> Vec3 {
+        let origin = match *self.placement {
+            SpawnPlacement::Player => self.in_front_of_player(),
+            SpawnPlacement::Cursor => self.cursor().or_else(|| self.in_front_of_player()),
+        };
+
+        origin.unwrap_or(FALLBACK_POSITION)
+    }
+
+    pub fn in_front_of_player(&self) -> Option<Vec3> {
+        let player_transform = self.player_query.get_single().ok()?;
+
+        // the player model faces +z, so rotating it gives the walking direction
+        let facing = (player_transform.rotation * Vec3::Z).x;
+        let ahead = if facing.abs() > 0.1 {
+            facing.signum() * SPAWN_AHEAD
+        } else {
+            0.0
+        };
+
+        Some(player_transform.translation + Vec3::new(ahead, SPAWN_HEIGHT, 0.0))
+    }
+
+    pub fn cursor(&self) -> Option<Vec3> {
+        let (cm, ct) = self.camera_query.get_single().ok()?;
+        let cursor_pos = self.window_query.get_single().ok()?.cursor_position()?;
+
+        let cursor_ray = cm.viewport_to_world(ct, cursor_pos)?;
+        let distance = cursor_ray.intersect_plane(Vec3::ZERO, Plane3d::new(Vec3::Z))?;
+
+        Some(cursor_ray.get_point(distance))
+    }
+
+    /// Moves `translation` upwards until `collider`, scaled like `transform`,
+    /// no longer overlaps anything in the world.
+    pub fn resolve_overlap(&self, collider: &Collider, transform: &Transform) -> Vec3 {
+        let mut shape = collider.clone();
+        shape.set_scale(transform.scale, 4);
+
+        let mut translation = transform.translation;
+        for _ in 0..OVERLAP_MAX_ATTEMPTS {
+            let hit = self.rapier_context.intersection_with_shape(
+                translation,
+                transform.rotation,
+                &shape,
+                QueryFilter::default().exclude_sensors(),
+            );
+
+            if hit.is_none() {
+                break;
+            }
+
+            translation.y += OVERLAP_STEP * transform.scale.y.max(1.0);
+        }
+
+        translation
+    }
+}
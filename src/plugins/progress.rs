@@ -0,0 +1,141 @@
+use bevy::prelude::*;
+
+use super::{
+    fallback::RetryGeneration,
+    ui::{MeshLoading, MeshLoadingFailed, MeshLoadingTimeout},
+};
+
+const LABEL_FONT_SIZE: f32 = 18.0;
+const LABEL_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
+const LABEL_FAILED_COLOR: Color = Color::rgb(1.0, 0.4, 0.4);
+const LABEL_BACKGROUND_COLOR: Color = Color::rgba(0.15, 0.15, 0.15, 0.8);
+const PROGRESS_BAR_WIDTH: usize = 10;
+/// Height above the placeholder's origin, in units of its scale.
+const LABEL_OFFSET: f32 = 1.5;
+
+pub struct GenerationProgressPlugin;
+
+impl Plugin for GenerationProgressPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (spawn_generation_labels, update_generation_labels).chain(),
+        );
+    }
+}
+
+/// Screen-space label that follows a `MeshLoading` entity.
+#[derive(Component)]
+struct GenerationLabel {
+    target: Entity,
+}
+
+fn spawn_generation_labels(mut commands: Commands, query: Query<Entity, Added<MeshLoading>>) {
+    for target in query.iter() {
+        commands.spawn((
+            GenerationLabel { target },
+            TextBundle::from_section(
+                "",
+                TextStyle {
+                    font_size: LABEL_FONT_SIZE,
+                    color: LABEL_COLOR,
+                    ..default()
+                },
+            )
+            .with_style(Style {
+                position_type: PositionType::Absolute,
+                padding: UiRect::all(Val::Px(4.0)),
+                ..default()
+            })
+            .with_background_color(LABEL_BACKGROUND_COLOR),
+        ));
+    }
+}
+
+type TargetQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static GlobalTransform,
+        &'static MeshLoading,
+        &'static MeshLoadingTimeout,
+        Option<&'static MeshLoadingFailed>,
+        Option<&'static RetryGeneration>,
+    ),
+>;
+
+fn update_generation_labels(
+    mut commands: Commands,
+    camera_query: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+    target_query: TargetQuery,
+    mut label_query: Query<(
+        Entity,
+        &GenerationLabel,
+        &Node,
+        &mut Text,
+        &mut Style,
+        &mut Visibility,
+    )>,
+) {
+    let Ok((camera, camera_transform)) = camera_query.get_single() else {
+        return;
+    };
+
+    for (entity, label, node, mut text, mut style, mut visibility) in label_query.iter_mut() {
+        // the model arrived or the placeholder was removed
+        let Ok((transform, loading, timeout, failed, retry)) = target_query.get(label.target)
+        else {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        };
+
+        let (scale, _, translation) = transform.to_scale_rotation_translation();
+        let anchor = translation + Vec3::Y * LABEL_OFFSET * scale.y;
+
+        match camera.world_to_viewport(camera_transform, anchor) {
+            Some(position) => {
+                style.left = Val::Px(position.x - node.size().x / 2.0);
+                style.top = Val::Px(position.y - node.size().y);
+                *visibility = Visibility::Visible;
+            }
+            None => *visibility = Visibility::Hidden,
+        }
+
+        let section = &mut text.sections[0];
+        match (failed, retry) {
+            (Some(failed), _) => {
+                section.value = format!("{}: {}", loading.noun, failed.reason);
+                section.style.color = LABEL_FAILED_COLOR;
+            }
+            (None, Some(retry)) => {
+                section.value = format!(
+                    "{} retrying in {:.0}s",
+                    loading.noun,
+                    retry.remaining_secs()
+                );
+                section.style.color = LABEL_COLOR;
+            }
+            (None, None) => {
+                let mut value = format!(
+                    "{} {:.0}s / {:.0}s",
+                    loading.noun,
+                    timeout.elapsed_secs(),
+                    timeout.duration().as_secs_f32()
+                );
+
+                if let Some(progress) = loading.progress {
+                    let progress = progress.clamp(0.0, 1.0);
+                    let filled = (progress * PROGRESS_BAR_WIDTH as f32).round() as usize;
+                    value.push_str(&format!(
+                        "\n[{}{}] {:.0}%",
+                        "#".repeat(filled),
+                        "-".repeat(PROGRESS_BAR_WIDTH - filled),
+                        progress * 100.0
+                    ));
+                }
+
+                section.value = value;
+            }
+        }
+    }
+}
@@ -0,0 +1,258 @@
+use std::{
+    fs::OpenOptions,
+    io,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use super::{
+    command::{
+        ArgKind, CommandFeedback, CommandInvocation, CommandSet, CommandSpec, RegisterCommand,
+    },
+    history::{History, ObjectSnapshot, RestoreObject},
+    player::Player,
+    ui::{ObjectId, ObjectOrigin, SpawnedObject},
+};
+
+pub const SAVES_DIR: &str = "saves";
+/// `/save` refuses names starting with this, they belong to the rotating autosaves.
+pub const AUTOSAVE_PREFIX: &str = "autosave";
+
+pub struct SavePlugin;
+
+impl Plugin for SavePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_command(
+            CommandSpec::new("save", "Stores every object and the player in a file")
+                .arg("name", ArgKind::Noun),
+        )
+        .register_command(
+            CommandSpec::new("load", "Replaces the world with a saved one")
+                .arg("name", ArgKind::Noun),
+        )
+        .add_event::<SaveLoaded>()
+        .add_systems(Update, run_save_commands.in_set(CommandSet::Handle));
+    }
+}
+
+/// Sent when `/load` replaced the world with a save file.
+#[derive(Event, Debug, Clone, Copy)]
+pub struct SaveLoaded;
+
+/// A spawned object as stored in a save file.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct SavedObject {
+    pub id: u64,
+    /// Spawned again on load, which also regenerates its model if it is missing.
+    pub phrase: String,
+    pub translation: [f32; 3],
+    pub rotation: [f32; 4],
+    pub scale: [f32; 3],
+    pub linvel: [f32; 3],
+    pub angvel: [f32; 3],
+    #[serde(default)]
+    pub frozen: bool,
+    /// Id of the object this one is glued to.
+    #[serde(default)]
+    pub glued_to: Option<u64>,
+}
+
+impl SavedObject {
+    fn transform(&self) -> Transform {
+        Transform {
+            translation: Vec3::from(self.translation),
+            rotation: Quat::from_array(self.rotation),
+            scale: Vec3::from(self.scale),
+        }
+    }
+
+    fn velocity(&self) -> Velocity {
+        Velocity {
+            linvel: Vec3::from(self.linvel),
+            angvel: Vec3::from(self.angvel),
+        }
+    }
+}
+
+/// Everything in the sandbox, stored in `saves/<name>.ron`.
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
+#[serde(default)]
+pub struct SandboxSave {
+    pub player: Option<[f32; 3]>,
+    pub objects: Vec<SavedObject>,
+}
+
+pub type SavedObjectQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        &'static ObjectId,
+        &'static ObjectOrigin,
+        &'static Transform,
+        Option<&'static Velocity>,
+        Option<&'static RigidBody>,
+        Option<&'static ImpulseJoint>,
+    ),
+    With<SpawnedObject>,
+>;
+
+pub type PlayerQuery<'w, 's> = Query<
+    'w,
+    's,
+    (&'static mut Transform, &'static mut Velocity),
+    (With<Player>, Without<SpawnedObject>),
+>;
+
+impl SandboxSave {
+    pub fn path(name: &str) -> PathBuf {
+        Path::new(SAVES_DIR).join(name).with_extension("ron")
+    }
+
+    pub fn capture(object_query: &SavedObjectQuery, player: Option<&Transform>) -> Self {
+        let objects = object_query
+            .iter()
+            .map(|(id, origin, transform, velocity, body, joint)| {
+                let velocity = velocity.copied().unwrap_or_default();
+                let glued_to = joint
+                    .and_then(|joint| object_query.get(joint.parent).ok())
+                    .map(|(parent_id, ..)| parent_id.0);
+
+                SavedObject {
+                    id: id.0,
+                    phrase: origin.phrase.clone(),
+                    translation: transform.translation.to_array(),
+                    rotation: transform.rotation.to_array(),
+                    scale: transform.scale.to_array(),
+                    linvel: velocity.linvel.to_array(),
+                    angvel: velocity.angvel.to_array(),
+                    frozen: body == Some(&RigidBody::Fixed),
+                    glued_to,
+                }
+            })
+            .collect();
+
+        SandboxSave {
+            player: player.map(|transform| transform.translation.to_array()),
+            objects,
+        }
+    }
+
+    pub fn import(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().read(true).open(path)?;
+
+        ron::de::from_reader(file).map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))
+    }
+
+    pub fn export(&self, path: &Path) -> io::Result<()> {
+        let content = ron::ser::to_string_pretty(self, Default::default())
+            .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        std::fs::write(path, content)
+    }
+
+    /// Despawns every object and spawns the saved ones in their place. Objects
+    /// whose model is missing are queued for generation by `spawn_listener`.
+    pub fn restore(
+        &self,
+        commands: &mut Commands,
+        history: &mut History,
+        restores: &mut EventWriter<RestoreObject>,
+        object_query: &Query<Entity, With<SpawnedObject>>,
+        player_query: &mut PlayerQuery,
+    ) {
+        for entity in object_query.iter() {
+            commands.entity(entity).despawn_recursive();
+        }
+
+        // ids in the history would point at the saved objects
+        history.clear();
+
+        if let (Some(translation), Ok((mut transform, mut velocity))) =
+            (self.player, player_query.get_single_mut())
+        {
+            transform.translation = Vec3::from(translation);
+            *velocity = Velocity::zero();
+        }
+
+        restores.send_batch(self.objects.iter().map(|object| {
+            RestoreObject(ObjectSnapshot {
+                id: ObjectId(object.id),
+                phrase: object.phrase.clone(),
+                transform: object.transform(),
+                velocity: object.velocity(),
+                frozen: object.frozen,
+                glued_to: object.glued_to.map(ObjectId),
+            })
+        }));
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_save_commands(
+    mut commands: Commands,
+    mut history: ResMut<History>,
+    mut invocations: EventReader<CommandInvocation>,
+    mut restores: EventWriter<RestoreObject>,
+    mut loaded: EventWriter<SaveLoaded>,
+    mut feedback: CommandFeedback,
+    saved_query: SavedObjectQuery,
+    object_query: Query<Entity, With<SpawnedObject>>,
+    mut player_query: PlayerQuery,
+) {
+    for invocation in invocations.read() {
+        match (invocation.name.as_str(), invocation.word(0)) {
+            ("save", Some(name)) if name.starts_with(AUTOSAVE_PREFIX) => {
+                feedback.send(format!("{} is kept for autosaves, pick another name", name));
+            }
+            ("save", Some(name)) => {
+                let path = SandboxSave::path(name);
+                let player = player_query
+                    .get_single()
+                    .ok()
+                    .map(|(transform, _)| transform);
+                let save = SandboxSave::capture(&saved_query, player);
+
+                match save.export(&path) {
+                    Ok(()) => feedback.send(format!(
+                        "Saved {} objects to {}",
+                        save.objects.len(),
+                        path.display()
+                    )),
+                    Err(err) => {
+                        feedback.send(format!("Failed to save {}: {}", path.display(), err))
+                    }
+                }
+            }
+            ("load", Some(name)) => {
+                let path = SandboxSave::path(name);
+                let save = match SandboxSave::import(&path) {
+                    Ok(save) => save,
+                    Err(err) if err.kind() == io::ErrorKind::NotFound => {
+                        feedback.send(format!("No save named {}", name));
+                        continue;
+                    }
+                    Err(err) => {
+                        feedback.send(format!("Failed to read {}: {}", path.display(), err));
+                        continue;
+                    }
+                };
+
+                save.restore(
+                    &mut commands,
+                    &mut history,
+                    &mut restores,
+                    &object_query,
+                    &mut player_query,
+                );
+                loaded.send(SaveLoaded);
+            }
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use std::{collections::VecDeque, io, path::Path};
+
+use bevy::prelude::*;
+
+use super::{
+    command::{
+        ArgKind, CommandFeedback, CommandInvocation, CommandLine, CommandSet, CommandSpec,
+        RegisterCommand,
+    },
+    ui::{submit_line, SpawnPhrase},
+};
+
+/// `scrb --run scene.txt` runs the script once the game starts.
+const RUN_FLAG: &str = "--run";
+
+/// How deep scripts may `/run` each other, so that one running itself stops.
+const MAX_SCRIPT_DEPTH: usize = 8;
+
+pub struct ScriptPlugin;
+
+impl Plugin for ScriptPlugin {
+    fn build(&self, app: &mut App) {
+        let mut runner = ScriptRunner::default();
+
+        if let Some(path) = std::env::args().skip_while(|arg| arg != RUN_FLAG).nth(1) {
+            if let Err(err) = runner.load(&path, 0.0, 1) {
+                println!("Failed to run {}: {}", path, err);
+            }
+        }
+
+        app.insert_resource(runner)
+            .register_command(
+                CommandSpec::new(
+                    "run",
+                    "Types every line of a file, optionally waiting seconds between lines",
+                )
+                .arg("file", ArgKind::Word)
+                .optional_arg("delay", ArgKind::Number),
+            )
+            .add_systems(
+                Update,
+                (
+                    run_script_lines.in_set(CommandSet::Submit),
+                    run_script_commands.in_set(CommandSet::Handle),
+                ),
+            );
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum ScriptLine {
+    /// A phrase or a `/command`.
+    Submit(String),
+    /// `wait <seconds>` pauses the script.
+    Wait(f32),
+}
+
+impl ScriptLine {
+    /// Blank lines and `#` comments are skipped.
+    fn parse(line: &str) -> Option<Self> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return None;
+        }
+
+        if let Some(seconds) = line.strip_prefix("wait ") {
+            match seconds.trim().parse() {
+                Ok(seconds) => return Some(ScriptLine::Wait(seconds)),
+                Err(_) => println!("Invalid wait {:?} in script", seconds),
+            }
+            return None;
+        }
+
+        Some(ScriptLine::Submit(line.to_string()))
+    }
+}
+
+/// Lines of running scripts, one submitted per frame. Its commands are handled
+/// within the same frame, see [`CommandSet`], so each line sees the effects of
+/// the ones before it.
+#[derive(Resource, Default)]
+pub struct ScriptRunner {
+    /// Each line with how deep in `/run`s its script is.
+    lines: VecDeque<(usize, ScriptLine)>,
+    wait: Timer,
+    /// Depth of the script whose line was submitted this frame.
+    submitting: Option<usize>,
+}
+
+impl ScriptRunner {
+    /// Queues the lines of the file at `path` in front of whatever is running,
+    /// so a script can `/run` another one. `depth` is 1 for a script that no
+    /// other script ran.
+    pub fn load(&mut self, path: impl AsRef<Path>, delay: f32, depth: usize) -> io::Result<()> {
+        let content = std::fs::read_to_string(path)?;
+
+        let mut lines = vec![];
+        for line in content.lines().filter_map(ScriptLine::parse) {
+            let submit = matches!(line, ScriptLine::Submit(_));
+            lines.push(line);
+            if submit && delay > 0.0 {
+                lines.push(ScriptLine::Wait(delay));
+            }
+        }
+
+        for line in lines.into_iter().rev() {
+            self.lines.push_front((depth, line));
+        }
+
+        Ok(())
+    }
+}
+
+fn run_script_commands(
+    mut runner: ResMut<ScriptRunner>,
+    mut invocations: EventReader<CommandInvocation>,
+    mut feedback: CommandFeedback,
+) {
+    for invocation in invocations.read() {
+        if invocation.name != "run" {
+            continue;
+        }
+
+        let Some(path) = invocation.word(0) else {
+            continue;
+        };
+        let delay = invocation.number(1).unwrap_or(0.0);
+
+        let depth = runner.submitting.map_or(1, |depth| depth + 1);
+        if depth > MAX_SCRIPT_DEPTH {
+            feedback.send(format!(
+                "Not running {}: scripts can only /run {} deep",
+                path, MAX_SCRIPT_DEPTH
+            ));
+            continue;
+        }
+
+        if let Err(err) = runner.load(path, delay, depth) {
+            feedback.send(format!("Failed to run {}: {}", path, err));
+        }
+    }
+}
+
+fn run_script_lines(
+    time: Res<Time>,
+    mut runner: ResMut<ScriptRunner>,
+    mut command_lines: EventWriter<CommandLine>,
+    mut phrases: EventWriter<SpawnPhrase>,
+) {
+    runner.submitting = None;
+
+    if runner.lines.is_empty() || !runner.wait.tick(time.delta()).finished() {
+        return;
+    }
+
+    match runner.lines.pop_front() {
+        Some((depth, ScriptLine::Submit(line))) => {
+            runner.submitting = Some(depth);
+            submit_line(&line, &mut command_lines, &mut phrases);
+        }
+        Some((_, ScriptLine::Wait(seconds))) => {
+            runner.wait = Timer::from_seconds(seconds.max(0.0), TimerMode::Once);
+        }
+        None => {}
+    }
+}
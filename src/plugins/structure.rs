@@ -0,0 +1,199 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use super::{
+    command::{
+        ArgKind, CommandFeedback, CommandInvocation, CommandSet, CommandSpec, RegisterCommand,
+    },
+    notification::Notification,
+    player::Player,
+    ui::{target_objects, ObjectId, SpawnedObject, TargetQuery},
+};
+
+/// Frames a restored object waits for the object it was glued to before giving up.
+const GLUE_RESTORE_FRAMES: u32 = 10;
+
+pub struct StructurePlugin;
+
+impl Plugin for StructurePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_command(
+            CommandSpec::new(
+                "freeze",
+                "Fixes objects in place, the selected ones unless a target is given",
+            )
+            .optional_arg("target", ArgKind::Word)
+            .optional_arg("radius", ArgKind::Number),
+        )
+        .register_command(
+            CommandSpec::new("unfreeze", "Lets frozen objects move again")
+                .optional_arg("target", ArgKind::Word)
+                .optional_arg("radius", ArgKind::Number),
+        )
+        .register_command(
+            CommandSpec::new("glue", "Joins two objects so they move as one")
+                .arg("a", ArgKind::Word)
+                .arg("b", ArgKind::Word),
+        )
+        .add_systems(
+            Update,
+            (
+                run_structure_commands.in_set(CommandSet::Handle),
+                restore_glue,
+            ),
+        );
+    }
+}
+
+/// A restored object that was glued to `parent`, joined again once both exist.
+#[derive(Component, Debug, Clone, Copy)]
+pub struct PendingGlue {
+    pub parent: ObjectId,
+    frames: u32,
+}
+
+impl PendingGlue {
+    pub fn new(parent: ObjectId) -> Self {
+        Self { parent, frames: 0 }
+    }
+}
+
+/// Keeps `child` where it is relative to `parent`, joined halfway between them.
+fn glue_joint(parent: &GlobalTransform, child: &GlobalTransform) -> FixedJointBuilder {
+    let (_, parent_rotation, parent_translation) = parent.to_scale_rotation_translation();
+    let (_, child_rotation, child_translation) = child.to_scale_rotation_translation();
+    let anchor = (parent_translation + child_translation) / 2.0;
+
+    FixedJointBuilder::new()
+        .local_basis1(parent_rotation.inverse())
+        .local_basis2(child_rotation.inverse())
+        .local_anchor1(parent_rotation.inverse() * (anchor - parent_translation))
+        .local_anchor2(child_rotation.inverse() * (anchor - child_translation))
+}
+
+fn run_structure_commands(
+    mut commands: Commands,
+    mut invocations: EventReader<CommandInvocation>,
+    mut feedback: CommandFeedback,
+    object_query: TargetQuery,
+    joint_query: Query<(), With<ImpulseJoint>>,
+    player_query: Query<&Transform, With<Player>>,
+) {
+    let player = player_query.get_single().ok();
+
+    for invocation in invocations.read() {
+        match invocation.name.as_str() {
+            name @ ("freeze" | "unfreeze") => {
+                let target = invocation.word(0).unwrap_or("selected");
+                let targets =
+                    match target_objects(target, invocation.number(1), &object_query, player) {
+                        Ok(targets) => targets,
+                        Err(err) => {
+                            feedback.send(err);
+                            continue;
+                        }
+                    };
+
+                if targets.is_empty() {
+                    feedback.send(format!("Nothing to {} for {}", name, target));
+                }
+
+                for entity in targets {
+                    if name == "freeze" {
+                        commands
+                            .entity(entity)
+                            .insert((RigidBody::Fixed, Velocity::zero()));
+                    } else {
+                        commands.entity(entity).insert(RigidBody::Dynamic);
+                    }
+                }
+            }
+            "glue" => {
+                let (Some(a), Some(b)) = (invocation.word(0), invocation.word(1)) else {
+                    continue;
+                };
+
+                // the newest object of each target, so `/glue cube cube` joins the last two cubes
+                let targets = target_objects(a, None, &object_query, player).and_then(|first| {
+                    let second = target_objects(b, None, &object_query, player)?;
+                    let parent = first.first().copied();
+                    let child = second.into_iter().find(|entity| Some(*entity) != parent);
+                    Ok(parent.zip(child))
+                });
+
+                let (parent, child) = match targets {
+                    Ok(Some(targets)) => targets,
+                    Ok(None) => {
+                        feedback.send(format!("Nothing to glue for {} and {}", a, b));
+                        continue;
+                    }
+                    Err(err) => {
+                        feedback.send(err);
+                        continue;
+                    }
+                };
+
+                // an object can only hold one joint, so it goes on whichever is free
+                let (parent, child) =
+                    match (joint_query.contains(parent), joint_query.contains(child)) {
+                        (_, false) => (parent, child),
+                        (false, true) => (child, parent),
+                        (true, true) => {
+                            feedback.send(format!(
+                                "{} and {} are both glued to something already",
+                                a, b
+                            ));
+                            continue;
+                        }
+                    };
+
+                let (Ok((.., parent_transform, _)), Ok((.., child_transform, _))) =
+                    (object_query.get(parent), object_query.get(child))
+                else {
+                    continue;
+                };
+
+                commands.entity(child).insert(ImpulseJoint::new(
+                    parent,
+                    glue_joint(parent_transform, child_transform),
+                ));
+            }
+            _ => {}
+        }
+    }
+}
+
+fn restore_glue(
+    mut commands: Commands,
+    mut notifications: EventWriter<Notification>,
+    mut pending_query: Query<(Entity, &ObjectId, &mut PendingGlue, &Transform)>,
+    object_query: Query<(Entity, &ObjectId, &Transform), With<SpawnedObject>>,
+) {
+    for (child, child_id, mut pending, child_transform) in pending_query.iter_mut() {
+        let Some((parent, _, parent_transform)) = object_query
+            .iter()
+            .find(|(_, id, _)| **id == pending.parent)
+        else {
+            // the parent may be gone for good, e.g. when only the glued object was undone
+            pending.frames += 1;
+            if pending.frames >= GLUE_RESTORE_FRAMES {
+                commands.entity(child).remove::<PendingGlue>();
+                notifications.send(Notification::new(format!(
+                    "Could not glue object {} again, object {} is gone",
+                    child_id.0, pending.parent.0
+                )));
+            }
+            continue;
+        };
+
+        commands.entity(child).remove::<PendingGlue>();
+
+        commands.entity(child).insert(ImpulseJoint::new(
+            parent,
+            glue_joint(
+                &GlobalTransform::from(*parent_transform),
+                &GlobalTransform::from(*child_transform),
+            ),
+        ));
+    }
+}
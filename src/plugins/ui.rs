@@ -1,10 +1,9 @@
-use std::io::Write;
+use std::collections::HashMap;
 
 use backends::rapier::RapierPickable;
 use bevy::{
     input::{mouse::MouseButtonInput, ButtonState},
     prelude::*,
-    utils::HashMap,
 };
 use bevy_eventlistener::event_listener::On;
 use bevy_mod_picking::prelude::*;
@@ -13,14 +12,34 @@ use bevy_simple_text_input::{TextInputBundle, TextInputSubmitEvent};
 
 use crate::components::{
     core::LockedAxesBundle,
-    modifier::{Dictionary, Modifier},
+    modifier::{Dictionary, Modifier, ModifierName},
+    noun::{noun_slug, NounRegistry},
 };
 
-use super::player::Player;
+use super::{
+    collider::PendingCollider,
+    command::{
+        ArgKind, CommandFeedback, CommandInvocation, CommandLine, CommandSet, CommandSpec,
+        RegisterCommand,
+    },
+    fallback::RetryGeneration,
+    generator::{
+        queue::{GenerationQueue, GenerationUpdate},
+        Generator,
+    },
+    history::{ObjectSnapshot, ObjectSpawned, RemoveObject, RestoreObject},
+    normalize::PendingNormalization,
+    notification::Notification,
+    placement::SpawnPlacer,
+    player::Player,
+    structure::PendingGlue,
+};
 
 const BORDER_COLOR_ACTIVE: Color = Color::rgb(0.75, 0.52, 0.99);
 const TEXT_COLOR: Color = Color::rgb(0.9, 0.9, 0.9);
 const BACKGROUND_COLOR: Color = Color::rgb(0.15, 0.15, 0.15);
+/// The model shown while an object waits for its own.
+pub const PLACEHOLDER_NOUN: &str = "mystery_block";
 
 #[derive(Component)]
 pub struct TypingUi;
@@ -33,20 +52,36 @@ pub enum TypingState {
 }
 
 #[derive(Component, Deref, DerefMut)]
-struct MeshLoadingPoller(Timer);
+pub struct MeshLoadingTimeout(Timer);
 
-impl Default for MeshLoadingPoller {
+impl Default for MeshLoadingTimeout {
     fn default() -> Self {
-        Self(Timer::from_seconds(1.0, TimerMode::Repeating))
+        Self(Timer::from_seconds(120.0, TimerMode::Once))
     }
 }
 
-#[derive(Component, Deref, DerefMut)]
+/// A placeholder whose generation failed, kept briefly so the failure can be shown.
+#[derive(Component)]
+pub struct MeshLoadingFailed {
+    pub reason: String,
+    /// Until a fallback is applied, see [`super::fallback::FallbackStrategy`].
+    pub timer: Timer,
+}
 
-struct MeshLoadingTimeout(Timer);
-impl Default for MeshLoadingTimeout {
-    fn default() -> Self {
-        Self(Timer::from_seconds(120.0, TimerMode::Once))
+impl MeshLoadingFailed {
+    fn new(reason: &str) -> Self {
+        Self {
+            reason: reason.to_string(),
+            timer: Timer::from_seconds(3.0, TimerMode::Once),
+        }
+    }
+
+    /// Nothing can be generated, so there is no failure to show before the fallback.
+    fn offline() -> Self {
+        Self {
+            reason: "mesh generation is offline".to_string(),
+            timer: Timer::from_seconds(0.0, TimerMode::Once),
+        }
     }
 }
 
@@ -55,32 +90,49 @@ pub struct MainUiPlugin;
 impl Plugin for MainUiPlugin {
     fn build(&self, app: &mut App) {
         app.init_state::<TypingState>()
-            .add_systems(Startup, run_python_backend)
+            .register_type::<ObjectId>()
+            .register_type::<ObjectOrigin>()
+            .add_event::<SpawnPhrase>()
+            .register_command(CommandSpec::new("clear", "Removes every spawned object"))
+            .register_command(
+                CommandSpec::new(
+                    "cancel",
+                    "Drops a queued model and the objects waiting for it",
+                )
+                .arg("noun", ArgKind::Noun),
+            )
+            .register_command(
+                CommandSpec::new(
+                    "delete",
+                    "Removes objects by noun, the last one, the selected ones, or those near the player",
+                )
+                .arg("target", ArgKind::Word)
+                .optional_arg("radius", ArgKind::Number),
+            )
             .add_systems(OnEnter(TypingState::IsTyping), setup_ui_on_typing)
             .add_systems(OnExit(TypingState::IsTyping), kill_ui_on_typing)
             .add_systems(
                 Update,
                 (
                     typing_toggler,
-                    command_listener,
-                    spawn_listener,
+                    command_listener.in_set(CommandSet::Submit),
+                    run_object_commands.in_set(CommandSet::Handle),
+                    spawn_listener.after(CommandSet::Handle),
                     update_handle_drag,
                 ),
             )
             .add_systems(
                 Update,
-                poll_mesh_until_loaded_or_timeout.run_if(any_with_component::<MeshLoading>),
+                (tick_mesh_loading_timeout, on_generation_update)
+                    .run_if(any_with_component::<MeshLoading>),
             )
+            .add_systems(Update, cancel_abandoned_generations)
             .add_systems(
                 Update,
                 on_drag_end_despawn.run_if(any_with_component::<PickingAnchor>),
             )
             .add_systems(Update, on_drag_start);
         // .add_systems(Update, click_listener);
-        // .add_systems(
-        //     Update,
-        //     attach_collider_to_scene.run_if(any_with_component::<PendingCollider>),
-        // );
     }
 }
 
@@ -213,49 +265,130 @@ fn on_drag_start(
     }
 }
 
-fn poll_mesh_until_loaded_or_timeout(
-    time: Res<Time>,
-    mut commands: Commands,
-    mut timeout: Local<HashMap<Entity, MeshLoadingTimeout>>,
-    mut timer: Local<MeshLoadingPoller>,
-    asset_server: Res<AssetServer>,
-    query: Query<(Entity, &Transform, &MeshLoading)>,
+/// Drops pending jobs once every object waiting for them is gone, e.g. after
+/// `/clear`, `/delete` or undo. Jobs already sent to the backend run to the end.
+fn cancel_abandoned_generations(
+    mut queue: ResMut<GenerationQueue>,
+    mut removed: RemovedComponents<MeshLoading>,
+    mut waiting: Local<HashMap<Entity, String>>,
+    changed_query: Query<(Entity, &MeshLoading), Changed<MeshLoading>>,
+    loading_query: Query<&MeshLoading>,
 ) {
-    if timer.tick(time.delta()).just_finished() {
-        for (entity, transform, mesh_loading) in query.iter() {
-            match timeout.get_mut(&entity) {
-                Some(timer) => {
-                    if timer.tick(time.delta()).just_finished() {
-                        {
-                            commands.entity(entity).despawn_recursive();
-                            timeout.remove(&entity);
-                        }
-                    }
+    for (entity, loading) in changed_query.iter() {
+        waiting.insert(entity, loading.noun.clone());
+    }
 
-                    let noun = &mesh_loading.noun;
-                    if std::path::Path::new(&format!("assets/models/{noun}/mesh.glb")).exists() {
-                        println!("Successfully loaded {}", noun);
+    for entity in removed.read() {
+        let Some(noun) = waiting.remove(&entity) else {
+            continue;
+        };
 
-                        commands
-                            .entity(entity)
-                            .remove::<MeshLoading>()
-                            .insert(SceneBundle {
-                                scene: asset_server.load(format!("models/{noun}/mesh.glb#Scene0")),
-                                transform: *transform,
-                                ..default()
-                            });
+        if !loading_query.iter().any(|loading| loading.noun == noun) {
+            queue.cancel(&noun);
+        }
+    }
+}
 
-                        timeout.remove(&entity);
-                    }
+fn tick_mesh_loading_timeout(
+    time: Res<Time>,
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut MeshLoadingTimeout), Without<MeshLoadingFailed>>,
+) {
+    for (entity, mut timeout) in query.iter_mut() {
+        if timeout.tick(time.delta()).just_finished() {
+            commands
+                .entity(entity)
+                .insert(MeshLoadingFailed::new("timed out"));
+        }
+    }
+}
+
+fn on_generation_update(
+    mut events: EventReader<GenerationUpdate>,
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    mut noun_registry: ResMut<NounRegistry>,
+    mut query: Query<(Entity, &Transform, &mut MeshLoading), Without<MeshLoadingFailed>>,
+) {
+    for event in events.read() {
+        // every placeholder waiting for the noun gets the same result
+        for (entity, transform, mut mesh_loading) in query.iter_mut() {
+            let noun = mesh_loading.noun.clone();
+            match event {
+                GenerationUpdate::Progress {
+                    noun: target,
+                    progress,
+                } if *target == noun => {
+                    mesh_loading.progress = Some(*progress);
+                }
+                GenerationUpdate::Finished { noun: target } if *target == noun => {
+                    println!("Successfully generated {}", noun);
+
+                    finish_mesh_loading(
+                        &mut commands,
+                        entity,
+                        transform,
+                        &noun,
+                        &asset_server,
+                        &mut noun_registry,
+                    );
                 }
-                None => {
-                    timeout.insert(entity, MeshLoadingTimeout::default());
+                GenerationUpdate::Failed {
+                    noun: target,
+                    reason,
+                } if *target == noun => {
+                    println!("Failed to generate {}: {}", noun, reason);
+
+                    commands
+                        .entity(entity)
+                        .insert(MeshLoadingFailed::new(reason));
                 }
+                _ => {}
             }
         }
     }
 }
 
+/// Swaps the placeholder of a `MeshLoading` entity for the model of `noun`.
+pub fn finish_mesh_loading(
+    commands: &mut Commands,
+    entity: Entity,
+    transform: &Transform,
+    noun: &str,
+    asset_server: &AssetServer,
+    noun_registry: &mut NounRegistry,
+) {
+    let entry = noun_registry.get(noun);
+
+    commands
+        .entity(entity)
+        .remove::<(
+            MeshLoading,
+            MeshLoadingTimeout,
+            MeshLoadingFailed,
+            RetryGeneration,
+        )>()
+        .insert((
+            SceneBundle {
+                scene: asset_server.load(format!("models/{noun}/mesh.glb#Scene0")),
+                transform: *transform,
+                ..default()
+            },
+            entry.collider.collider(),
+            ColliderMassProperties::Density(entry.density),
+            PendingNormalization {
+                noun: noun.to_string(),
+            },
+        ));
+
+    if let Some(kind) = entry.collider.mesh_collider() {
+        commands.entity(entity).insert(PendingCollider {
+            noun: noun.to_string(),
+            kind,
+        });
+    }
+}
+
 fn kill_ui_on_typing(mut commands: Commands, ui_query: Query<Entity, With<TypingUi>>) {
     if let Ok(entity) = ui_query.get_single() {
         commands.entity(entity).despawn_recursive();
@@ -301,204 +434,400 @@ fn setup_ui_on_typing(mut commands: Commands) {
 
 fn command_listener(
     mut events: EventReader<TextInputSubmitEvent>,
-    mut commands: Commands,
-    everything_query: Query<(Entity, &SpawnedObject)>,
+    mut command_lines: EventWriter<CommandLine>,
+    mut phrases: EventWriter<SpawnPhrase>,
 ) {
-    for event in events.read() {
-        let TextInputSubmitEvent { value, .. } = event;
+    for TextInputSubmitEvent { value, .. } in events.read() {
+        submit_line(value, &mut command_lines, &mut phrases);
+    }
+}
 
-        if value.starts_with('/') {
-            handle_command(value, &mut commands, &everything_query);
-        }
+/// Handles a line as if it was typed: `/` starts a command, anything else is spawned.
+pub fn submit_line(
+    line: &str,
+    command_lines: &mut EventWriter<CommandLine>,
+    phrases: &mut EventWriter<SpawnPhrase>,
+) {
+    if line.starts_with('/') {
+        command_lines.send(CommandLine(line.to_string()));
+    } else {
+        phrases.send(SpawnPhrase(line.to_string()));
     }
 }
 
 #[allow(clippy::too_many_arguments)]
 fn spawn_listener(
-    mut events: EventReader<TextInputSubmitEvent>,
+    mut events: EventReader<SpawnPhrase>,
     mut commands: Commands,
     asset_server: Res<AssetServer>,
     mut meshes: ResMut<Assets<Mesh>>,
     mut materials: ResMut<Assets<StandardMaterial>>,
     mut dictionary: ResMut<Dictionary>,
-    mut python_stdin: ResMut<PythonStdin>,
+    mut noun_registry: ResMut<NounRegistry>,
+    generator: Res<Generator>,
+    mut queue: ResMut<GenerationQueue>,
+    placer: SpawnPlacer,
+    mut restores: EventReader<RestoreObject>,
+    mut spawned: EventWriter<ObjectSpawned>,
+    mut notifications: EventWriter<Notification>,
+    mut next_id: Local<u64>,
 ) {
-    for event in events.read() {
-        let TextInputSubmitEvent { value, .. } = event;
+    let requests: Vec<(String, Option<ObjectSnapshot>)> = events
+        .read()
+        .map(|SpawnPhrase(phrase)| (phrase.clone(), None))
+        .chain(
+            restores
+                .read()
+                .map(|RestoreObject(snapshot)| (snapshot.phrase.clone(), Some(snapshot.clone()))),
+        )
+        .collect();
+
+    for (value, snapshot) in requests {
+        let mut parts = value.split_whitespace().rev();
+        if parts.clone().count() < 1 {
+            continue;
+        }
 
-        if !value.starts_with('/') {
-            let mut parts = value.split_whitespace().rev();
-            if parts.clone().count() < 1 {
-                return;
+        let Some(slug) = noun_slug(parts.next().unwrap_or("ball")) else {
+            notifications.send(Notification(format!("No valid noun in {:?}", value)));
+            continue;
+        };
+        let noun = slug.as_str();
+
+        // restored objects keep their id so history entries still find them
+        let id = match &snapshot {
+            Some(snapshot) => {
+                // ids from a save file must not be handed out again
+                *next_id = (*next_id).max(snapshot.id.0);
+                snapshot.id
             }
+            None => {
+                *next_id += 1;
+                ObjectId(*next_id)
+            }
+        };
 
-            let mut ent = commands.spawn((
-                RapierPickable,
-                RigidBody::Dynamic,
-                LockedAxesBundle::default(),
-                PickableBundle::default(),
-                SpawnedObject,
-                ColliderMassProperties::Density(1.0),
-                On::<Pointer<DragStart>>::target_commands_mut(|_, cmd| {
-                    cmd.insert(Pickable::IGNORE);
-                }), // Disable picking
-                On::<Pointer<DragEnd>>::target_commands_mut(|_, cmd| {
-                    cmd.insert(Pickable::default());
-                    cmd.insert(Velocity {
-                        angvel: Vec3::ZERO,
-                        linvel: Vec3::ZERO,
-                    });
-                }), // Enable picking
-            ));
-
-            let noun = parts.next().unwrap_or("ball");
+        let mut ent = commands.spawn((
+            RapierPickable,
+            RigidBody::Dynamic,
+            LockedAxesBundle::default(),
+            PickableBundle::default(),
+            SpawnedObject,
+            id,
+            On::<Pointer<DragStart>>::target_commands_mut(|_, cmd| {
+                cmd.insert(Pickable::IGNORE);
+            }), // Disable picking
+            On::<Pointer<DragEnd>>::target_commands_mut(|_, cmd| {
+                cmd.insert(Pickable::default());
+                cmd.insert(Velocity {
+                    angvel: Vec3::ZERO,
+                    linvel: Vec3::ZERO,
+                });
+            }), // Enable picking
+        ));
 
-            let collider = match noun {
-                "cube" => Collider::cuboid(0.5, 0.5, 0.5),
-                "ball" => Collider::ball(0.5),
-                _ => Collider::cuboid(0.5, 0.5, 0.5),
-            };
-            let mut material = StandardMaterial::default();
-            let mut transform = Transform::from_xyz(0.0, 20.0, 0.0);
-            for adj in parts {
-                if let Some(entry) = dictionary.search(adj).first() {
-                    for modifier in entry.modifier.clone() {
-                        match modifier {
-                            Modifier::ColorModifier(color) => material.base_color = color,
-                            Modifier::ScaleModifier(scale) => {
-                                transform.scale = Vec3::splat(scale);
-                                transform.translation.y += scale * 0.5;
-                            }
-                            Modifier::RoughnessModifier(roughness) => {
-                                material.perceptual_roughness = if roughness < 0.089 {
-                                    0.089
-                                } else if roughness > 1.0 {
-                                    1.0
-                                } else {
-                                    roughness
-                                };
-                            }
-                            Modifier::MetallicModifier(metallic) => {
-                                material.metallic = if metallic < 0.0 {
-                                    0.0
-                                } else if metallic > 1.0 {
-                                    1.0
-                                } else {
-                                    metallic
-                                };
-                            }
-                            Modifier::ReflectanceModifier(reflectance) => {
-                                material.reflectance = if reflectance < 0.0 {
-                                    0.0
-                                } else if reflectance > 1.0 {
-                                    1.0
-                                } else {
-                                    reflectance
-                                };
-                            }
+        let entry = noun_registry.get(noun);
+
+        ent.insert(ColliderMassProperties::Density(entry.density));
+
+        let collider = entry.collider.collider();
+        let mut material = StandardMaterial::default();
+        let mut transform =
+            Transform::from_translation(placer.origin()).with_scale(Vec3::splat(entry.scale));
+        let mut modifiers = vec![];
+        for adj in parts {
+            if let Some(modifier_name) = dictionary.search(adj).first() {
+                modifiers.push(modifier_name.clone());
+                for modifier in modifier_name.modifier.clone() {
+                    match modifier {
+                        Modifier::ColorModifier(color) => material.base_color = color,
+                        Modifier::ScaleModifier(scale) => {
+                            transform.scale = Vec3::splat(entry.scale * scale);
+                            transform.translation.y += entry.scale * scale * 0.5;
+                        }
+                        Modifier::RoughnessModifier(roughness) => {
+                            material.perceptual_roughness = if roughness < 0.089 {
+                                0.089
+                            } else if roughness > 1.0 {
+                                1.0
+                            } else {
+                                roughness
+                            };
+                        }
+                        Modifier::MetallicModifier(metallic) => {
+                            material.metallic = if metallic < 0.0 {
+                                0.0
+                            } else if metallic > 1.0 {
+                                1.0
+                            } else {
+                                metallic
+                            };
+                        }
+                        Modifier::ReflectanceModifier(reflectance) => {
+                            material.reflectance = if reflectance < 0.0 {
+                                0.0
+                            } else if reflectance > 1.0 {
+                                1.0
+                            } else {
+                                reflectance
+                            };
                         }
                     }
                 }
             }
+        }
 
-            let shape: MeshOrScene = match noun {
-                "cube" => MeshOrScene::Mesh(Mesh::from(Cuboid::new(1.0, 1.0, 1.0))),
-                "ball" => MeshOrScene::Mesh(Mesh::from(Sphere::new(0.5))),
-                _ => {
-                    if !std::path::Path::new(&format!("assets/models/{noun}/mesh.glb")).exists() {
-                        let stdin = &mut python_stdin.stdin;
-
-                        if writeln!(stdin, "{}", noun).is_ok() {
-                            let noun = noun.to_string();
-                            MeshOrScene::Loading(noun)
-                        } else {
-                            MeshOrScene::Mesh(Mesh::from(Cuboid::new(1.0, 1.0, 1.0)))
-                        }
-                    } else {
-                        MeshOrScene::Scene(
-                            asset_server.load(format!("models/{noun}/mesh.glb#Scene0")),
-                        )
-                    }
-                }
-            };
+        // parts are read from the noun backwards
+        modifiers.reverse();
+        ent.insert(ObjectOrigin {
+            phrase: value.clone(),
+            noun: slug.clone(),
+            modifiers,
+        });
 
-            match shape {
-                MeshOrScene::Mesh(mesh) => {
-                    ent.insert((
-                        PbrBundle {
-                            mesh: meshes.add(mesh),
-                            material: materials.add(material),
-                            transform,
-                            ..default()
-                        },
-                        collider,
-                    ));
+        match &snapshot {
+            Some(snapshot) => {
+                transform = snapshot.transform;
+                ent.insert(snapshot.velocity);
+
+                if snapshot.frozen {
+                    ent.insert(RigidBody::Fixed);
                 }
-                MeshOrScene::Scene(model) => {
-                    ent.insert((
-                        SceneBundle {
-                            scene: model,
-                            transform: transform.with_rotation(
-                                Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2)
-                                    * Quat::from_rotation_z(-std::f32::consts::FRAC_PI_2),
-                            ),
-                            ..default()
-                        },
-                        collider,
-                    ));
+                if let Some(parent) = snapshot.glued_to {
+                    ent.insert(PendingGlue::new(parent));
                 }
-                MeshOrScene::Loading(noun) => {
-                    ent.insert((
-                        SceneBundle {
-                            scene: asset_server.load("models/mystery_block/mesh.glb#Scene0"),
-                            transform: transform.with_rotation(
-                                Quat::from_rotation_x(-std::f32::consts::FRAC_PI_2)
-                                    * Quat::from_rotation_z(-std::f32::consts::FRAC_PI_2),
-                            ),
-                            ..default()
-                        },
-                        collider,
-                        MeshLoading { noun },
-                    ));
+            }
+            None => {
+                transform.translation = placer.resolve_overlap(&collider, &transform);
+                spawned.send(ObjectSpawned(id));
+            }
+        }
+
+        let shape: MeshOrScene = match noun {
+            "cube" => MeshOrScene::Mesh(Mesh::from(Cuboid::new(1.0, 1.0, 1.0))),
+            "ball" => MeshOrScene::Mesh(Mesh::from(Sphere::new(0.5))),
+            _ => {
+                if !std::path::Path::new(&format!("assets/models/{noun}/mesh.glb")).exists() {
+                    // offline, the fallback strategies pick a stand-in right away
+                    if generator.is_offline() {
+                        ent.insert(MeshLoadingFailed::offline());
+                    } else {
+                        queue.enqueue(noun);
+                    }
+                    MeshOrScene::Loading(noun.to_string())
+                } else {
+                    MeshOrScene::Scene(asset_server.load(format!("models/{noun}/mesh.glb#Scene0")))
                 }
-                MeshOrScene::MeshHandle(handle) => {
-                    ent.insert((
-                        PbrBundle {
-                            mesh: handle,
-                            material: materials.add(material),
-                            transform,
-                            ..default()
-                        },
-                        collider,
-                    ));
+            }
+        };
+
+        match shape {
+            MeshOrScene::Mesh(mesh) => {
+                ent.insert((
+                    PbrBundle {
+                        mesh: meshes.add(mesh),
+                        material: materials.add(material),
+                        transform,
+                        ..default()
+                    },
+                    collider,
+                ));
+            }
+            MeshOrScene::Scene(model) => {
+                ent.insert((
+                    SceneBundle {
+                        scene: model,
+                        transform,
+                        ..default()
+                    },
+                    collider,
+                    PendingNormalization {
+                        noun: noun.to_string(),
+                    },
+                ));
+
+                if let Some(kind) = entry.collider.mesh_collider() {
+                    ent.insert(PendingCollider {
+                        noun: noun.to_string(),
+                        kind,
+                    });
                 }
             }
+            MeshOrScene::Loading(noun) => {
+                ent.insert((
+                    SceneBundle {
+                        scene: asset_server
+                            .load(format!("models/{PLACEHOLDER_NOUN}/mesh.glb#Scene0")),
+                        transform,
+                        ..default()
+                    },
+                    collider,
+                    PendingNormalization {
+                        noun: PLACEHOLDER_NOUN.to_string(),
+                    },
+                    MeshLoading {
+                        noun,
+                        progress: None,
+                        attempts: 0,
+                        material: materials.add(material),
+                    },
+                    MeshLoadingTimeout::default(),
+                ));
+            }
+            MeshOrScene::MeshHandle(handle) => {
+                ent.insert((
+                    PbrBundle {
+                        mesh: handle,
+                        material: materials.add(material),
+                        transform,
+                        ..default()
+                    },
+                    collider,
+                ));
+            }
         }
     }
 }
 
-fn handle_command(
-    value: &str,
-    commands: &mut Commands,
-    everything_query: &Query<(Entity, &SpawnedObject)>,
+pub type TargetQuery<'w, 's> = Query<
+    'w,
+    's,
+    (
+        Entity,
+        &'static ObjectId,
+        &'static ObjectOrigin,
+        &'static GlobalTransform,
+        Option<&'static PickSelection>,
+    ),
+    With<SpawnedObject>,
+>;
+
+/// The objects a command refers to with a noun, `last`, `selected`, or
+/// `within` and a `radius` around the player. Newest first.
+pub fn target_objects(
+    target: &str,
+    radius: Option<f32>,
+    object_query: &TargetQuery,
+    player: Option<&Transform>,
+) -> Result<Vec<Entity>, String> {
+    let mut objects: Vec<(&ObjectId, Entity)> = match (target, radius) {
+        ("last", _) => object_query
+            .iter()
+            .max_by_key(|(_, id, ..)| id.0)
+            .map(|(entity, id, ..)| (id, entity))
+            .into_iter()
+            .collect(),
+        ("selected", _) => object_query
+            .iter()
+            .filter(|(.., selection)| selection.is_some_and(|s| s.is_selected))
+            .map(|(entity, id, ..)| (id, entity))
+            .collect(),
+        ("within", Some(radius)) => {
+            let Some(player) = player else {
+                return Err("No player to measure from".to_string());
+            };
+            object_query
+                .iter()
+                .filter(|(_, _, _, transform, _)| {
+                    transform.translation().distance(player.translation) <= radius
+                })
+                .map(|(entity, id, ..)| (id, entity))
+                .collect()
+        }
+        ("within", None) => return Err("within needs a radius".to_string()),
+        (noun, _) => {
+            let Some(noun) = noun_slug(noun) else {
+                return Err(format!("{:?} is not a valid noun", noun));
+            };
+            object_query
+                .iter()
+                .filter(|(_, _, origin, ..)| origin.noun == noun)
+                .map(|(entity, id, ..)| (id, entity))
+                .collect()
+        }
+    };
+
+    objects.sort_by_key(|(id, _)| std::cmp::Reverse(id.0));
+    Ok(objects.into_iter().map(|(_, entity)| entity).collect())
+}
+
+#[allow(clippy::too_many_arguments)]
+fn run_object_commands(
+    mut commands: Commands,
+    mut queue: ResMut<GenerationQueue>,
+    mut invocations: EventReader<CommandInvocation>,
+    mut removals: EventWriter<RemoveObject>,
+    mut feedback: CommandFeedback,
+    object_query: TargetQuery,
+    loading_query: Query<(Entity, &MeshLoading)>,
+    player_query: Query<&Transform, With<Player>>,
 ) {
-    let mut parts = value.trim_start_matches('/').split_whitespace();
-    let noun = parts.next().unwrap_or("clear");
-
-    #[allow(clippy::single_match)]
-    match noun {
-        "clear" => {
-            for (entity, _) in everything_query.iter() {
-                commands.entity(entity).despawn_recursive();
+    for invocation in invocations.read() {
+        match (invocation.name.as_str(), invocation.word(0)) {
+            ("clear", _) => {
+                removals.send_batch(object_query.iter().map(|(entity, ..)| RemoveObject(entity)));
+            }
+            ("cancel", Some(target)) => {
+                if queue.cancel(target) {
+                    for (entity, mesh_loading) in loading_query.iter() {
+                        if mesh_loading.noun == target {
+                            commands.entity(entity).despawn_recursive();
+                        }
+                    }
+                } else if queue.contains(target) {
+                    feedback.send(format!(
+                        "{} is already being generated and cannot be cancelled",
+                        target
+                    ));
+                } else {
+                    feedback.send(format!("Nothing queued for {}", target));
+                }
+            }
+            ("delete", Some(target)) => {
+                let player = player_query.get_single().ok();
+                let targets =
+                    match target_objects(target, invocation.number(1), &object_query, player) {
+                        Ok(targets) => targets,
+                        Err(err) => {
+                            feedback.send(err);
+                            continue;
+                        }
+                    };
+
+                if targets.is_empty() {
+                    feedback.send(format!("Nothing to delete for {}", target));
+                }
+                removals.send_batch(targets.into_iter().map(RemoveObject));
             }
+            _ => {}
         }
-        _ => {}
     }
 }
 
 #[derive(Component)]
 pub struct SpawnedObject;
 
+/// Stays the same when an object is removed and restored, unlike its `Entity`.
+#[derive(Component, Reflect, Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[reflect(Component)]
+pub struct ObjectId(pub u64);
+
+/// What a spawned object was made from.
+#[derive(Component, Reflect, Debug, Clone, Default)]
+#[reflect(Component)]
+pub struct ObjectOrigin {
+    /// The text as it was typed.
+    pub phrase: String,
+    /// Slug of the last word of the phrase.
+    pub noun: String,
+    /// Dictionary entries of the words before the noun that were found, in typed order.
+    pub modifiers: Vec<ModifierName>,
+}
+
+/// Adjectives followed by a noun, e.g. `big red cake`, to be spawned as an object.
+#[derive(Event, Debug, Clone)]
+pub struct SpawnPhrase(pub String);
+
 pub enum MeshOrScene {
     Mesh(Mesh),
     Scene(Handle<Scene>),
@@ -508,7 +837,13 @@ pub enum MeshOrScene {
 
 #[derive(Component)]
 pub struct MeshLoading {
-    noun: String,
+    pub noun: String,
+    /// Last progress reported by the generator, between 0 and 1.
+    pub progress: Option<f32>,
+    /// Generation retries so far.
+    pub attempts: u32,
+    /// Built from the requested modifiers, for a primitive stand-in.
+    pub material: Handle<StandardMaterial>,
 }
 
 #[derive(Component)]
@@ -517,37 +852,6 @@ pub enum PbrOrScene {
     Scene(SceneBundle),
 }
 
-#[derive(Component)]
-pub struct PendingCollider;
-
-#[derive(Resource)]
-pub struct PythonStdin {
-    pub stdin: std::process::ChildStdin,
-}
-
-fn run_python_backend(mut commands: Commands) {
-    // run subprocess
-    let mut child = std::process::Command::new("/Users/quatton/.pyenv/versions/tsr/bin/python")
-        .current_dir("/Users/quatton/Documents/GitHub/TripoSR")
-        .arg("fal.py")
-        .arg("--output-dir")
-        .arg("/Users/quatton/Documents/GitHub/scrb/assets/models")
-        .arg("--no-remove-bg")
-        .arg("--pipe-to-3d")
-        .arg("--mc-resolution")
-        .arg("32")
-        // pipe stdin
-        .stdin(std::process::Stdio::piped())
-        .stdout(std::process::Stdio::null())
-        // background this
-        .spawn()
-        .expect("failed to execute process");
-
-    commands.insert_resource(PythonStdin {
-        stdin: child.stdin.take().unwrap(),
-    });
-}
-
 #[derive(Component)]
 pub struct PickingAnchor;
 
@@ -595,3 +899,67 @@ fn typing_toggler(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        path::Path,
+        time::{Duration, Instant},
+    };
+
+    use bevy::asset::AssetPlugin;
+
+    use super::*;
+    use crate::plugins::generator::{procedural::TestModel, GeneratorConfig, GeneratorPlugin};
+
+    #[test]
+    fn procedural_model_replaces_the_placeholder() {
+        let model = TestModel("mesh_loading_test");
+        let noun = model.0;
+
+        let mut app = App::new();
+        app.add_plugins((MinimalPlugins, AssetPlugin::default(), GeneratorPlugin))
+            .init_asset::<Scene>()
+            .init_resource::<NounRegistry>()
+            .add_systems(Update, on_generation_update);
+
+        // the configured backend is set up on the first update
+        app.update();
+        app.insert_resource(Generator::new(&GeneratorConfig::Procedural { delay: 0.0 }));
+
+        let entity = app
+            .world
+            .spawn((
+                Transform::default(),
+                MeshLoading {
+                    noun: noun.to_string(),
+                    progress: None,
+                    attempts: 0,
+                    material: Handle::default(),
+                },
+                MeshLoadingTimeout::default(),
+            ))
+            .id();
+        app.world.resource_mut::<GenerationQueue>().enqueue(noun);
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while app.world.get::<MeshLoading>(entity).is_some() && Instant::now() < deadline {
+            app.update();
+            std::thread::sleep(Duration::from_millis(10));
+        }
+
+        assert!(Path::new("assets/models")
+            .join(noun)
+            .join("mesh.glb")
+            .exists());
+        let entity = app.world.entity(entity);
+        assert!(!entity.contains::<MeshLoading>());
+        assert!(entity.contains::<Handle<Scene>>());
+        assert_eq!(
+            entity
+                .get::<PendingNormalization>()
+                .map(|p| p.noun.as_str()),
+            Some(noun)
+        );
+    }
+}